@@ -7,7 +7,9 @@ mod bridge {
     use erc20::Erc20Ref;
 
     use ink_env::call::FromAccountId;
-    use ink_storage::traits::{PackedLayout, SpreadAllocate, SpreadLayout};
+    use ink_env::hash::{Blake2x256, Keccak256};
+    use ink_prelude::vec::Vec;
+    use ink_storage::traits::{PackedAllocate, PackedLayout, SpreadAllocate, SpreadLayout};
 
     use thiserror_no_std::Error;
 
@@ -16,60 +18,685 @@ mod bridge {
     pub enum Error {
         #[error("You need to transfer at least some tokens")]
         ZeroAmount,
+        #[error("Arithmetic overflow")]
+        Overflow,
         #[error("Not enough allowance for transfer, allowance: {allowance:?}, amount: {amount:?}")]
         Allowance { allowance: u128, amount: u128 },
         #[error("Erc20 error")]
         Erc20(#[from] erc20::erc20::Error),
         #[error("Transfer '{0}' not found")]
         NotFound(u128),
-        #[error("It's not acceptable to refund successful transfer")]
-        RefundSuccessfulTransfer,
-        #[error("It's not acceptable to try_again successful transfer")]
-        TryAgainSuccessfulTransfer,
-        #[error("It's not acceptable to process unsuccessful transfer")]
-        ProcessUnsuccessfulTransfer,
+        #[error("Transfer '{0:?}' not found")]
+        GlobalIdNotFound(Hash),
+        #[error("Transfer '{id}' can't become {to:?} from {from:?}")]
+        InvalidTransition {
+            id: u128,
+            from: TransferStatus,
+            to: TransferStatus,
+        },
         #[error("Bridge doesn't have enough amount, balance: {balance:?}, amount: {amount:?}")]
         InsufficientBridgeBalance { balance: Balance, amount: Balance },
+        #[error("Payout would leave token '{token:?}' short of its liabilities by {deficit:?}")]
+        Insolvent { token: AccountId, deficit: Balance },
         #[error("Unexpected error")]
         Unexpected,
         #[error("Only executor is able to process queued transfers")]
         ExecutorPermissionDenied,
         #[error("Only sender is able to refund unsuccessful transfer")]
         RefundPermissionDenied,
+        #[error("Ethereum transfer '{0:?}' is already released")]
+        AlreadyReleased(Hash),
+        #[error("Executor has already voted for transfer '{0}'")]
+        AlreadyVoted(u128),
+        #[error("Executor has already voted differently for transfer '{0}'")]
+        ConflictingVote(u128),
+        #[error("Only admin is able to manage the bridge")]
+        AdminPermissionDenied,
+        #[error("Only pending admin is able to accept ownership")]
+        PendingAdminPermissionDenied,
+        #[error("Account '{0:?}' is not an executor")]
+        UnknownExecutor(ExecutorId),
+        #[error("Account '{0:?}' is already an executor")]
+        DuplicateExecutor(ExecutorId),
+        #[error(
+            "Threshold must be between 1 and the number of executors {executors}, got: {threshold}"
+        )]
+        InvalidThreshold { threshold: u32, executors: u32 },
+        #[error("Signature '{0}' can't be recovered")]
+        InvalidSignature(u32),
+        #[error("Executor '{0:?}' signed more than once")]
+        DuplicateSignature([u8; 20]),
+        #[error("Only pauser is able to pause and unpause the bridge")]
+        PauserPermissionDenied,
+        #[error("Operation {0:?} is paused")]
+        Paused(Operation),
+        #[error("Token '{0:?}' is not registered")]
+        UnsupportedToken(AccountId),
+        #[error("Bridge is not the minter of token '{0:?}'")]
+        BridgeIsNotMinter(AccountId),
+        #[error("Mode of token '{0:?}' can't change while it has outstanding transfers")]
+        TokenModeLocked(AccountId),
+        #[error("Fee basis points must not exceed 10000, got: {0:?}")]
+        InvalidFee(u16),
+        #[error("Fee doesn't leave anything to transfer, amount: {amount:?}, fee: {fee:?}")]
+        FeeExceedsAmount { amount: Balance, fee: Balance },
+        #[error("Amount is below the minimum, amount: {amount:?}, minimum: {min_amount:?}")]
+        BelowMinimum {
+            amount: Balance,
+            min_amount: Balance,
+        },
+        #[error("Amount is above the maximum, amount: {amount:?}, maximum: {max_amount:?}")]
+        AboveMaximum {
+            amount: Balance,
+            max_amount: Balance,
+        },
+        #[error(
+            "{direction:?} volume limit exceeded, amount: {amount:?}, remaining: {remaining:?}"
+        )]
+        RateLimitExceeded {
+            direction: Direction,
+            amount: Balance,
+            remaining: Balance,
+        },
+        #[error("Rate limit window must not be zero")]
+        InvalidRateLimitWindow,
+        #[error("Only sender is able to reclaim queued transfer")]
+        ReclaimPermissionDenied,
+        #[error("Queued transfer can't be reclaimed before {available_at:?}")]
+        ReclaimTooEarly { available_at: Timestamp },
+        #[error("Only sender is able to cancel queued transfer")]
+        CancelPermissionDenied,
+        #[error("Queued transfer can't be cancelled after {closed_at:?}")]
+        CancelWindowClosed { closed_at: Timestamp },
+        #[error("Lease duration must not be zero")]
+        InvalidLeaseDuration,
+        #[error("Transfer '{id}' is leased by '{holder:?}' until {expires_at:?}")]
+        LeaseHeld {
+            id: u128,
+            holder: ExecutorId,
+            expires_at: Timestamp,
+        },
+        #[error(
+            "Only the holder of a valid lease is able to report the outcome of transfer '{0}' first"
+        )]
+        LeasePermissionDenied(u128),
+        #[error("Signature was valid until {0:?}")]
+        SignatureExpired(Timestamp),
+        #[error("Failure detail must not exceed {max} bytes, got: {len}")]
+        FailureDetailTooLong { len: u32, max: u32 },
+        #[error("Transfer '{0}' failed because of its destination, which has to be changed first")]
+        DestinationChangeRequired(u128),
+        #[error("Transfer '{id}' failed with {code:?} and can't be tried again")]
+        RetryNotAllowed { id: u128, code: FailureCode },
+        #[error("Only sender or its approved operator is able to try again failed transfer")]
+        RetryPermissionDenied,
+        #[error("Transfer '{id}' has already been tried again {max_retries} times")]
+        TooManyRetries { id: u128, max_retries: u32 },
+        #[error("Failed transfer can't be tried again before {available_at:?}")]
+        RetryTooEarly { available_at: Timestamp },
+        #[error("Only sender is able to change the destination of failed transfer")]
+        DestinationChangePermissionDenied,
+        #[error("Transfer '{0}' is already sent to this destination")]
+        SameDestination(u128),
+        #[error("Ethereum source '{0:?}' is not trusted")]
+        UnknownSource(EthSource),
+        #[error("Code hash '{0:?}' can't be set")]
+        CodeUpgradeFailed(Hash),
+        #[error("Deadline {0:?} has already passed")]
+        InvalidDeadline(Timestamp),
+        #[error("Transfer '{id}' can't be delivered after its deadline {deadline:?}")]
+        DeadlinePassed { id: u128, deadline: Timestamp },
+        #[error("Transfer '{id}' can't expire before its deadline {deadline:?}")]
+        DeadlineNotReached {
+            id: u128,
+            deadline: Option<Timestamp>,
+        },
+        #[error("Storage is already migrated to version {0}")]
+        AlreadyMigrated(u32),
+        #[error("Storage migration from version {0} is pending")]
+        MigrationPending(u32),
     }
 
     /// The ERC-20 result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// Groups of bridge messages which can be paused independently.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Operation {
+        /// Outbound deposits: `transfer`, `try_again` and `try_again_to`.
+        Deposit,
+        /// Executor work: `claim`, `process_transfer`, `process_transfers`,
+        /// `process_transfer_signed` and `expire`.
+        Process,
+        /// Returns to the sender: `refund`, `cancel` and `reclaim`.
+        Refund,
+        /// Inbound payouts: `release` and `release_signed`.
+        Release,
+    }
+
+    /// Key an executor votes with.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum ExecutorId {
+        /// Votes by calling the bridge from this account.
+        Account(AccountId),
+        /// Votes with attestations signed by the secp256k1 key of this Ethereum address,
+        /// the same key the executor uses for `Bridge.sol`.
+        Eth([u8; 20]),
+    }
+
+    // Enums can't derive the allocation traits required by `Vec<ExecutorId>` in the storage,
+    // the placeholder value is never read since the vector starts empty.
+    impl SpreadAllocate for ExecutorId {
+        fn allocate_spread(ptr: &mut ink_primitives::KeyPtr) -> Self {
+            ptr.advance_by(<Self as SpreadLayout>::FOOTPRINT);
+            Self::Account(AccountId::default())
+        }
+    }
+
+    impl PackedAllocate for ExecutorId {
+        fn allocate_packed(&mut self, _at: &ink_primitives::Key) {}
+    }
+
+    /// How the bridge moves a token between chains.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum TokenMode {
+        /// Outbound transfers lock tokens on the bridge, inbound transfers release them.
+        LockRelease,
+        /// The bridge is the minter of a wrapped token: outbound transfers burn it,
+        /// inbound transfers mint it.
+        MintBurn,
+    }
+
+    /// Registry entry of a token which can be bridged.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TokenInfo {
+        /// Address of the token counterpart on Ethereum.
+        eth_address: [u8; 20],
+        decimals: u8,
+        mode: TokenMode,
+        /// Unregistered tokens keep their entry so pending transfers can still be paid out.
+        enabled: bool,
+    }
+
+    /// Fee charged by `transfer`, it consists of a flat part and a part in basis points of the amount.
+    #[derive(
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        Clone,
+        Copy,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct FeeSchedule {
+        flat: Balance,
+        basis_points: u16,
+    }
+
+    impl FeeSchedule {
+        const MAX_BASIS_POINTS: u16 = 10_000;
+
+        fn fee_for(&self, amount: Balance) -> Balance {
+            let basis_points = Balance::from(self.basis_points);
+            let max_basis_points = Balance::from(Self::MAX_BASIS_POINTS);
+            let proportional = amount / max_basis_points * basis_points
+                + amount % max_basis_points * basis_points / max_basis_points;
+            self.flat.saturating_add(proportional)
+        }
+    }
+
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Direction {
+        /// From Substrate to Ethereum: `transfer`.
+        Outbound,
+        /// From Ethereum to Substrate: `release`.
+        Inbound,
+    }
+
+    /// Bounds of a single outbound transfer amount.
+    #[derive(
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        Clone,
+        Copy,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AmountLimits {
+        min_amount: Balance,
+        max_amount: Option<Balance>,
+    }
+
+    /// At most `cap` tokens can be bridged in one direction within `window` milliseconds.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct RateLimit {
+        cap: Balance,
+        window: Timestamp,
+    }
+
+    /// Bridged volume of the current and the previous window.
+    ///
+    /// The rolling usage is the current volume plus the previous volume weighted
+    /// by how much of the previous window still overlaps the last `window` milliseconds.
+    #[derive(
+        Debug,
+        Default,
+        PartialEq,
+        Eq,
+        scale::Encode,
+        scale::Decode,
+        Clone,
+        Copy,
+        SpreadLayout,
+        PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VolumeWindow {
+        started_at: Timestamp,
+        used: Balance,
+        previous_used: Balance,
+    }
+
+    impl VolumeWindow {
+        fn advance(self, now: Timestamp, window: Timestamp) -> Self {
+            let started_at = now - now % window;
+            if started_at == self.started_at {
+                self
+            } else if started_at == self.started_at + window {
+                Self {
+                    started_at,
+                    used: 0,
+                    previous_used: self.used,
+                }
+            } else {
+                Self {
+                    started_at,
+                    ..Default::default()
+                }
+            }
+        }
+
+        fn usage(&self, now: Timestamp, window: Timestamp) -> Balance {
+            let window = Balance::from(window);
+            let overlap = window - Balance::from(now - self.started_at);
+            let previous = self.previous_used / window * overlap
+                + self.previous_used % window * overlap / window;
+            self.used.saturating_add(previous)
+        }
+    }
+
+    /// Rolling volume usage reported by `get_volume_usage`.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct VolumeUsage {
+        used: Balance,
+        cap: Option<Balance>,
+        remaining: Option<Balance>,
+    }
+
+    /// Limits on `try_again` of a failed transfer.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct RetryPolicy {
+        max_retries: u32,
+        /// Time after a failure before the transfer can be tried again.
+        cooldown: Timestamp,
+    }
+
+    /// Balance of a token held by the bridge compared with what it owes, reported by `solvency`.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Solvency {
+        balance: Balance,
+        /// Locked amounts of queued and in-flight transfers.
+        queued: Balance,
+        /// Amounts of failed transfers which can still be refunded.
+        refundable: Balance,
+        collected_fees: Balance,
+        /// Tips of queued, in-flight and failed transfers.
+        tips: Balance,
+        /// Tips earned by `Eth` executors which they haven't withdrawn yet.
+        owed_tips: Balance,
+        /// Total paid out by `release`.
+        released: Balance,
+        /// Part of the balance the bridge owes, queued and refundable amounts
+        /// are burned in mint/burn mode so only the fees and tips are owed then.
+        liabilities: Balance,
+        surplus: Balance,
+        deficit: Balance,
+    }
+
+    /// Lifecycle state of an outbound transfer.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum TransferStatus {
+        /// Waiting for the executors to deliver it on Ethereum.
+        Queued,
+        /// Claimed by an executor which started the delivery on Ethereum.
+        InFlight,
+        /// Delivered on Ethereum.
+        Succeeded,
+        /// Delivery failed, it can be queued again or refunded.
+        Failed,
+        /// Returned to the sender after a failure.
+        Refunded,
+        /// Returned to the sender by `cancel` or `reclaim` while it was queued.
+        Cancelled,
+    }
+
+    impl TransferStatus {
+        fn can_become(self, next: TransferStatus) -> bool {
+            use TransferStatus::*;
+            matches!(
+                (self, next),
+                (Queued, InFlight)
+                    | (Queued, Failed)
+                    | (Queued, Cancelled)
+                    | (InFlight, Succeeded)
+                    | (InFlight, Failed)
+                    | (Failed, Queued)
+                    | (Failed, Refunded)
+            )
+        }
+    }
+
+    /// Ethereum transaction and log which delivered a transfer.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Settlement {
+        tx_hash: [u8; 32],
+        log_index: u32,
+    }
+
+    /// Why a delivery failed.
     #[derive(
         Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
     )]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum FailureCode {
+        /// The destination can't receive the tokens, it has to be changed before a retry.
+        InvalidDestination,
+        /// The Ethereum bridge doesn't hold enough tokens.
+        InsufficientLiquidity,
+        /// The token can't be delivered on Ethereum at all, the transfer can only be refunded.
+        UnsupportedToken,
+        /// The deadline of the transfer passed, set by `expire`.
+        Expired,
+        Other,
+    }
+
+    impl FailureCode {
+        fn ensure_retryable(self, transfer_id: u128) -> Result<()> {
+            match self {
+                FailureCode::InvalidDestination => {
+                    Err(Error::DestinationChangeRequired(transfer_id))
+                }
+                FailureCode::UnsupportedToken | FailureCode::Expired => {
+                    Err(Error::RetryNotAllowed {
+                        id: transfer_id,
+                        code: self,
+                    })
+                }
+                FailureCode::InsufficientLiquidity | FailureCode::Other => Ok(()),
+            }
+        }
+    }
+
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Failure {
+        code: FailureCode,
+        /// Free-form context from the executor, at most `MAX_FAILURE_DETAIL_LEN` bytes.
+        detail: Vec<u8>,
+    }
+
+    /// Result of a delivery attempt reported by an executor.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Outcome {
+        Succeeded(Settlement),
+        Failed(Failure),
+    }
+
+    /// Exclusive right of an executor to deliver an in-flight transfer.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct Lease {
+        holder: ExecutorId,
+        expires_at: Timestamp,
+    }
+
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct StatusChange {
+        status: TransferStatus,
+        timestamp: Timestamp,
+    }
+
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub struct Transfer {
+        /// Nonce of the transfer on this bridge.
         id: u128,
+        /// Identifier which is unique across chains and bridge deployments.
+        ///
+        /// It's derived from the fields at creation and never changes, so after `try_again_to`
+        /// it no longer matches `to`, the original destination is in `previous_destinations`.
+        global_id: Hash,
         from: AccountId,
+        token: AccountId,
         to: [u8; 20],
+        /// Amount locked from the sender.
+        gross_amount: Balance,
+        fee: Balance,
+        /// Amount delivered on Ethereum or refunded, `gross_amount - fee`.
         amount: Balance,
+        /// Locked on top of `gross_amount`, paid to the executor which claimed and delivered
+        /// the transfer or returned together with `amount`.
+        tip: Balance,
+        status: TransferStatus,
+        /// Every status the transfer went through, starting with `Queued`.
+        history: Vec<StatusChange>,
+        /// Where the transfer was delivered, set once it succeeded.
+        settlement: Option<Settlement>,
+        /// Time after which the transfer can't be delivered anymore.
+        deadline: Option<Timestamp>,
+        /// Latest claim of the transfer, it only matters while the transfer is in flight.
+        lease: Option<Lease>,
+        /// Reason of the latest failure.
+        failure: Option<Failure>,
+        /// Number of times the transfer was tried again after a failure.
+        retries: u32,
+        /// Destinations replaced by `try_again_to`, oldest first.
+        previous_destinations: Vec<[u8; 20]>,
+    }
+
+    impl Transfer {
+        fn transition(&mut self, status: TransferStatus, timestamp: Timestamp) -> Result<()> {
+            self.status
+                .can_become(status)
+                .then_some(())
+                .ok_or(Error::InvalidTransition {
+                    id: self.id,
+                    from: self.status,
+                    to: status,
+                })?;
+            self.status = status;
+            self.history.push(StatusChange { status, timestamp });
+            Ok(())
+        }
+
+        fn ensure_before_deadline(&self, now: Timestamp) -> Result<()> {
+            match self.deadline {
+                Some(deadline) if now > deadline => Err(Error::DeadlinePassed {
+                    id: self.id,
+                    deadline,
+                }),
+                _ => Ok(()),
+            }
+        }
+
+        /// Returns the lease of an in-flight transfer which hasn't expired yet.
+        fn active_lease(&self, now: Timestamp) -> Option<Lease> {
+            self.lease
+                .filter(|lease| self.status == TransferStatus::InFlight && now < lease.expires_at)
+        }
+
+        /// Returns the code of the failure the transfer is currently in.
+        fn failure_code(&self) -> Option<FailureCode> {
+            self.failure
+                .as_ref()
+                .filter(|_| self.status == TransferStatus::Failed)
+                .map(|failure| failure.code)
+        }
+
+        fn status_changed_at(&self) -> Timestamp {
+            self.history
+                .last()
+                .map(|change| change.timestamp)
+                .unwrap_or_default()
+        }
+    }
+
+    /// Either identifier of an outbound transfer.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum TransferKey {
+        Nonce(u128),
+        Global(Hash),
+    }
+
+    /// Ethereum chain and `Bridge.sol` deployment inbound transfers come from.
+    #[derive(
+        Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy, SpreadLayout, PackedLayout,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct EthSource {
+        pub chain_id: u64,
+        pub bridge: [u8; 20],
     }
 
+    /// Payout an executor votes for in `release`.
+    type ReleaseVote = (AccountId, AccountId, Balance);
+
     #[ink(storage)]
     #[derive(SpreadAllocate)]
     pub struct Bridge {
-        queue: ink_storage::Mapping<u128, Transfer>,
-        failed_transfers: ink_storage::Mapping<u128, Transfer>,
-        token_address: AccountId,
-        executor: AccountId,
+        transfers: ink_storage::Mapping<u128, Transfer>,
+        /// Ids of queued, in-flight and failed transfers by list position, kept dense by moving
+        /// the last id into the slot of a removed one.
+        status_lists: ink_storage::Mapping<(TransferStatus, u32), u128>,
+        status_list_positions: ink_storage::Mapping<u128, u32>,
+        status_list_lengths: ink_storage::Mapping<TransferStatus, u32>,
+        /// Ids of the transfers made by every sender, in the order they were made.
+        sender_transfers: ink_storage::Mapping<(AccountId, u32), u128>,
+        sender_transfer_counts: ink_storage::Mapping<AccountId, u32>,
+        /// Ids of the transfers sent to every Ethereum address, in the order they were made.
+        destination_transfers: ink_storage::Mapping<([u8; 20], u32), u128>,
+        destination_transfer_counts: ink_storage::Mapping<[u8; 20], u32>,
+        /// Inbound ids of the released Ethereum transfers, see `get_inbound_id`.
+        released_transfers: ink_storage::Mapping<Hash, ()>,
+        /// Time after which the sender can reclaim a transfer which is still queued.
+        reclaim_timeout: Timestamp,
+        tokens: ink_storage::Mapping<AccountId, TokenInfo>,
+        fees: ink_storage::Mapping<AccountId, FeeSchedule>,
+        collected_fees: ink_storage::Mapping<AccountId, Balance>,
+        amount_limits: ink_storage::Mapping<AccountId, AmountLimits>,
+        rate_limits: ink_storage::Mapping<(AccountId, Direction), RateLimit>,
+        volumes: ink_storage::Mapping<(AccountId, Direction), VolumeWindow>,
+        admin: AccountId,
+        pending_admin: Option<AccountId>,
+        pauser: AccountId,
+        paused: ink_storage::Mapping<Operation, bool>,
+        executors: Vec<ExecutorId>,
+        /// Bumped on every executor set change, votes of previous epochs are ignored.
+        executors_epoch: u32,
+        threshold: u32,
+        votes: ink_storage::Mapping<(u128, ExecutorId), (u32, Outcome)>,
+        vote_counts: ink_storage::Mapping<(u128, Outcome), (u32, u32)>,
+        release_votes: ink_storage::Mapping<(Hash, ExecutorId), (u32, ReleaseVote)>,
+        release_vote_counts: ink_storage::Mapping<(Hash, ReleaseVote), (u32, u32)>,
         counter: u128,
+        /// Version of the storage layout, `migrate` brings it up to `STORAGE_VERSION`
+        /// after a code upgrade. Fields added by an upgrade have to go after this one.
+        storage_version: u32,
+        /// Id of the chain the bridge runs on, part of every global transfer id.
+        chain_id: u64,
+        global_ids: ink_storage::Mapping<Hash, u128>,
+        queued_amounts: ink_storage::Mapping<AccountId, Balance>,
+        refundable_amounts: ink_storage::Mapping<AccountId, Balance>,
+        released_amounts: ink_storage::Mapping<AccountId, Balance>,
+        /// Tips of queued, in-flight and failed transfers.
+        locked_tips: ink_storage::Mapping<AccountId, Balance>,
+        /// Time after queueing during which the sender can `cancel` a transfer.
+        cancel_window: Timestamp,
+        max_retries: u32,
+        retry_cooldown: Timestamp,
+        /// Accounts allowed to try again failed transfers on behalf of their sender.
+        operators: ink_storage::Mapping<(AccountId, AccountId), ()>,
+        /// Tips earned by `Eth` executors per token, withdrawn with `withdraw_tips_signed`.
+        owed_tips: ink_storage::Mapping<([u8; 20], AccountId), Balance>,
+        owed_tip_totals: ink_storage::Mapping<AccountId, Balance>,
+        tip_withdrawal_nonces: ink_storage::Mapping<[u8; 20], u32>,
+        /// Ethereum chains and `Bridge.sol` deployments transfers are released from.
+        eth_sources: ink_storage::Mapping<EthSource, ()>,
     }
 
     #[ink(event)]
     pub struct Queued {
         #[ink(topic)]
         id: u128,
+        global_id: Hash,
         #[ink(topic)]
         from: AccountId,
+        token: AccountId,
         to: [u8; 20],
+        gross_amount: Balance,
+        fee: Balance,
         amount: Balance,
+        tip: Balance,
+        deadline: Option<Timestamp>,
         #[ink(topic)]
         timestamp: Timestamp,
     }
@@ -78,10 +705,15 @@ mod bridge {
     pub struct SuccessfulTransfer {
         #[ink(topic)]
         id: u128,
+        global_id: Hash,
         #[ink(topic)]
         from: AccountId,
+        token: AccountId,
         to: [u8; 20],
         amount: Balance,
+        settlement: Settlement,
+        tip: Balance,
+        tip_recipient: ExecutorId,
         #[ink(topic)]
         timestamp: Timestamp,
     }
@@ -90,10 +722,14 @@ mod bridge {
     pub struct FailedTransfer {
         #[ink(topic)]
         id: u128,
+        global_id: Hash,
         #[ink(topic)]
         from: AccountId,
+        token: AccountId,
         to: [u8; 20],
         amount: Balance,
+        tip: Balance,
+        failure: Failure,
         #[ink(topic)]
         timestamp: Timestamp,
     }
@@ -102,186 +738,4004 @@ mod bridge {
     pub struct Refund {
         #[ink(topic)]
         id: u128,
+        global_id: Hash,
         #[ink(topic)]
         to: AccountId,
+        token: AccountId,
         amount: Balance,
+        tip: Balance,
         #[ink(topic)]
         timestamp: Timestamp,
     }
 
-    impl Bridge {
-        fn get_erc20_ref(&self) -> Erc20Ref {
-            FromAccountId::from_account_id(self.token_address)
-        }
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        inbound_id: Hash,
+        source: EthSource,
+        eth_transfer_id: u128,
+        #[ink(topic)]
+        to: AccountId,
+        token: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(constructor)]
-        pub fn new(token: AccountId) -> Self {
-            ink_lang::utils::initialize_contract(|contract: &mut Self| {
-                contract.token_address = token;
-                contract.executor = Self::env().caller();
-                contract.counter = 0;
-            })
-        }
+    #[ink(event)]
+    pub struct Voted {
+        #[ink(topic)]
+        id: u128,
+        #[ink(topic)]
+        executor: ExecutorId,
+        outcome: Outcome,
+        votes: u32,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn get_transfer(&self, transfer_id: u128) -> Result<Option<(Transfer, bool)>> {
-            Ok(self
-                .queue
-                .get(transfer_id)
-                .map(|t| (t, true))
-                .or_else(|| self.failed_transfers.get(transfer_id).map(|t| (t, false))))
-        }
+    #[ink(event)]
+    pub struct ConflictingVotes {
+        #[ink(topic)]
+        id: u128,
+        /// Votes for the latest outcome.
+        votes: u32,
+        /// Votes for any other outcome.
+        conflicting_votes: u32,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn get_executor(&self) -> Result<AccountId> {
-            Ok(self.executor)
-        }
+    #[ink(event)]
+    pub struct ReleaseVoted {
+        #[ink(topic)]
+        inbound_id: Hash,
+        source: EthSource,
+        eth_transfer_id: u128,
+        #[ink(topic)]
+        executor: ExecutorId,
+        token: AccountId,
+        to: AccountId,
+        amount: Balance,
+        votes: u32,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn transfer(
-            &mut self,
-            amount: Balance,
-            external_destination_address: [u8; 20],
-        ) -> Result<u128> {
-            let caller = self.env().caller();
-            let contract = self.env().account_id();
-            (amount > 0).then(|| {}).ok_or(Error::ZeroAmount)?;
-            let mut token = Self::get_erc20_ref(self);
-            let allowance = token.allowance(caller, contract);
-            (allowance >= amount)
-                .then(|| {})
-                .ok_or(Error::Allowance { allowance, amount })?;
-            token.transfer_from(caller, contract, amount)?;
-            self.counter += 1;
-            self.queue.insert(
-                self.counter,
-                &Transfer {
-                    id: self.counter,
-                    from: caller,
-                    to: external_destination_address,
-                    amount,
-                },
-            );
-            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
-                self.env(),
-                Queued {
-                    id: self.counter,
-                    from: caller,
-                    to: external_destination_address,
-                    amount,
-                    timestamp: self.env().block_timestamp(),
-                },
-            );
-            Ok(self.counter)
-        }
+    #[ink(event)]
+    pub struct ExecutorChanged {
+        #[ink(topic)]
+        previous: ExecutorId,
+        #[ink(topic)]
+        executor: ExecutorId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn refund(&mut self, transfer_id: u128) -> Result<()> {
-            let (transfer, successful): (Transfer, bool) = self
-                .get_transfer(transfer_id)?
-                .ok_or(Error::NotFound(transfer_id))?;
-            if successful {
-                Err(Error::RefundSuccessfulTransfer)
-            } else {
-                let caller = self.env().caller();
-                if transfer.from != self.env().caller() {
-                    return Err(Error::RefundPermissionDenied);
-                }
-                let mut erc20_contract = self.get_erc20_ref();
-                let balance = erc20_contract.balance_of(self.env().account_id());
-                (balance >= transfer.amount)
-                    .then(|| {
-                        erc20_contract
-                            .transfer(transfer.from, transfer.amount)
-                            .map_err(Into::into)
-                            .and_then(|_| {
-                                self.failed_transfers.remove(transfer_id);
-                                ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
-                                    self.env(),
-                                    Refund {
-                                        id: transfer.id,
-                                        to: caller,
-                                        amount: transfer.amount,
-                                        timestamp: self.env().block_timestamp(),
-                                    },
-                                );
-                                Ok(())
-                            })
-                    })
-                    .ok_or(Error::InsufficientBridgeBalance {
-                        balance,
-                        amount: (transfer.amount),
-                    })?
-            }
-        }
+    #[ink(event)]
+    pub struct ExecutorAdded {
+        #[ink(topic)]
+        executor: ExecutorId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn try_again(&mut self, transfer_id: u128) -> Result<()> {
-            let (transfer, successful): (Transfer, bool) = self
-                .get_transfer(transfer_id)?
-                .ok_or(Error::NotFound(transfer_id))?;
-            if successful {
-                Err(Error::TryAgainSuccessfulTransfer)
-            } else {
-                self.queue.insert(transfer_id, &transfer);
-                self.failed_transfers.remove(transfer_id);
-                ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
-                    self.env(),
-                    Queued {
-                        id: transfer.id,
-                        from: transfer.from,
-                        to: transfer.to,
-                        amount: transfer.amount,
-                        timestamp: self.env().block_timestamp(),
-                    },
-                );
-                Ok(())
-            }
-        }
+    #[ink(event)]
+    pub struct ExecutorRemoved {
+        #[ink(topic)]
+        executor: ExecutorId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
 
-        #[ink(message)]
-        pub fn process_transfer(
-            &mut self,
-            transfer_id: u128,
-            mark_as_successful: bool,
+    #[ink(event)]
+    pub struct ThresholdChanged {
+        previous: u32,
+        threshold: u32,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        pending_admin: AccountId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_admin: AccountId,
+        #[ink(topic)]
+        admin: AccountId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        operation: Operation,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct PauserChanged {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        pauser: AccountId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct EthSourceChanged {
+        #[ink(topic)]
+        source: EthSource,
+        trusted: bool,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct TokenRegistered {
+        #[ink(topic)]
+        token: AccountId,
+        eth_address: [u8; 20],
+        decimals: u8,
+        mode: TokenMode,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct TokenUnregistered {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct FeeScheduleChanged {
+        #[ink(topic)]
+        token: AccountId,
+        flat: Balance,
+        basis_points: u16,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct TipsWithdrawn {
+        #[ink(topic)]
+        executor: [u8; 20],
+        #[ink(topic)]
+        token: AccountId,
+        to: AccountId,
+        amount: Balance,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct AmountLimitsChanged {
+        #[ink(topic)]
+        token: AccountId,
+        min_amount: Balance,
+        max_amount: Option<Balance>,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct RateLimitChanged {
+        #[ink(topic)]
+        token: AccountId,
+        #[ink(topic)]
+        direction: Direction,
+        rate_limit: Option<RateLimit>,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Cancelled {
+        #[ink(topic)]
+        id: u128,
+        global_id: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        token: AccountId,
+        amount: Balance,
+        tip: Balance,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ReclaimTimeoutChanged {
+        timeout: Timestamp,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct CancelWindowChanged {
+        window: Timestamp,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct RetryPolicyChanged {
+        policy: RetryPolicy,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct OperatorApproval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct DestinationChanged {
+        #[ink(topic)]
+        id: u128,
+        global_id: Hash,
+        old_destination: [u8; 20],
+        new_destination: [u8; 20],
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        id: u128,
+        global_id: Hash,
+        #[ink(topic)]
+        executor: ExecutorId,
+        expires_at: Timestamp,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Expired {
+        #[ink(topic)]
+        id: u128,
+        global_id: Hash,
+        #[ink(topic)]
+        from: AccountId,
+        deadline: Timestamp,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Upgraded {
+        #[ink(topic)]
+        old_code_hash: Hash,
+        #[ink(topic)]
+        new_code_hash: Hash,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Migrated {
+        from_version: u32,
+        to_version: u32,
+        #[ink(topic)]
+        timestamp: Timestamp,
+    }
+
+    impl Bridge {
+        /// Storage layout version this code works with.
+        const STORAGE_VERSION: u32 = 1;
+        /// A week in milliseconds.
+        const DEFAULT_RECLAIM_TIMEOUT: Timestamp = 7 * 24 * 60 * 60 * 1000;
+        /// Ten minutes in milliseconds.
+        const DEFAULT_CANCEL_WINDOW: Timestamp = 10 * 60 * 1000;
+        const DEFAULT_MAX_RETRIES: u32 = 3;
+        /// Ten minutes in milliseconds.
+        const DEFAULT_RETRY_COOLDOWN: Timestamp = 10 * 60 * 1000;
+        /// Domain separators of the digests signed by executors.
+        const PROCESS_DOMAIN: &'static [u8] = b"eth-substrate-bridge:process_transfer";
+        const CLAIM_DOMAIN: &'static [u8] = b"eth-substrate-bridge:claim";
+        const RELEASE_DOMAIN: &'static [u8] = b"eth-substrate-bridge:release";
+        const TRANSFER_DOMAIN: &'static [u8] = b"eth-substrate-bridge:transfer";
+        const INBOUND_DOMAIN: &'static [u8] = b"eth-substrate-bridge:inbound";
+        const TIPS_DOMAIN: &'static [u8] = b"eth-substrate-bridge:withdraw_tips";
+        /// Maximum number of transfers returned by a single list query.
+        const MAX_PAGE_SIZE: u32 = 100;
+        const MAX_FAILURE_DETAIL_LEN: u32 = 256;
+
+        fn get_erc20_ref(&self, token: AccountId) -> Erc20Ref {
+            FromAccountId::from_account_id(token)
+        }
+
+        fn ensure_registered(&self, token: AccountId) -> Result<TokenInfo> {
+            self.tokens
+                .get(token)
+                .filter(|info| info.enabled)
+                .ok_or(Error::UnsupportedToken(token))
+        }
+
+        /// Sends `amount` of `token` to `to`, minting it if the token is bridged in mint/burn mode.
+        ///
+        /// `settled` is the part of the liabilities the payout pays off.
+        fn pay_out(
+            &self,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+            settled: Balance,
         ) -> Result<()> {
-            (self.executor == self.env().caller())
-                .then(|| {})
-                .ok_or(Error::ExecutorPermissionDenied)?;
-            let (transfer, successful): (Transfer, bool) = self
-                .get_transfer(transfer_id)?
-                .ok_or(Error::NotFound(transfer_id))?;
-            if !successful {
-                Err(Error::ProcessUnsuccessfulTransfer)
-            } else {
-                self.queue.remove(transfer_id);
-                if mark_as_successful {
-                    ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
-                        self.env(),
-                        SuccessfulTransfer {
-                            id: transfer.id,
-                            from: transfer.from,
-                            to: transfer.to,
-                            amount: transfer.amount,
-                            timestamp: self.env().block_timestamp(),
-                        },
-                    );
+            let mut erc20_contract = self.get_erc20_ref(token);
+            match self.tokens.get(token).map(|info| info.mode) {
+                Some(TokenMode::MintBurn) => erc20_contract.mint(to, amount)?,
+                _ => {
+                    self.ensure_solvent_after(token, amount, settled)?;
+                    erc20_contract.transfer(to, amount)?;
+                }
+            }
+            Ok(())
+        }
+
+        fn liabilities(&self, token: AccountId) -> Balance {
+            let fees = self
+                .collected_fees
+                .get(token)
+                .unwrap_or_default()
+                .saturating_add(self.locked_tips.get(token).unwrap_or_default())
+                .saturating_add(self.owed_tip_totals.get(token).unwrap_or_default());
+            match self.tokens.get(token).map(|info| info.mode) {
+                Some(TokenMode::MintBurn) => fees,
+                _ => fees
+                    .saturating_add(self.queued_amounts.get(token).unwrap_or_default())
+                    .saturating_add(self.refundable_amounts.get(token).unwrap_or_default()),
+            }
+        }
+
+        /// Checks that the balance still covers the liabilities after paying out `amount`.
+        fn ensure_solvent_after(
+            &self,
+            token: AccountId,
+            amount: Balance,
+            settled: Balance,
+        ) -> Result<()> {
+            let balance = self
+                .get_erc20_ref(token)
+                .balance_of(self.env().account_id());
+            (balance >= amount)
+                .then_some(())
+                .ok_or(Error::InsufficientBridgeBalance { balance, amount })?;
+            let liabilities = self.liabilities(token).saturating_sub(settled);
+            (balance - amount >= liabilities)
+                .then_some(())
+                .ok_or(Error::Insolvent {
+                    token,
+                    deficit: liabilities - (balance - amount),
+                })
+        }
+
+        /// Adds or subtracts the transfer amount and tip from the running totals of its status.
+        fn account_outstanding(&mut self, transfer: &Transfer, status: TransferStatus, add: bool) {
+            let totals = match status {
+                TransferStatus::Queued | TransferStatus::InFlight => &mut self.queued_amounts,
+                TransferStatus::Failed => &mut self.refundable_amounts,
+                _ => return,
+            };
+            let adjust = |total: Balance, amount: Balance| {
+                if add {
+                    total.saturating_add(amount)
                 } else {
-                    self.failed_transfers.insert(transfer_id, &transfer);
-                    ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
-                        self.env(),
-                        FailedTransfer {
-                            id: transfer.id,
-                            from: transfer.from,
-                            to: transfer.to,
-                            amount: transfer.amount,
-                            timestamp: self.env().block_timestamp(),
-                        },
+                    total.saturating_sub(amount)
+                }
+            };
+            let total = totals.get(transfer.token).unwrap_or_default();
+            totals.insert(transfer.token, &adjust(total, transfer.amount));
+            let tips = self.locked_tips.get(transfer.token).unwrap_or_default();
+            self.locked_tips
+                .insert(transfer.token, &adjust(tips, transfer.tip));
+        }
+
+        /// Sends the tip of the transfer to `to`, it's always held on the bridge balance.
+        fn pay_tip(&self, transfer: &Transfer, to: AccountId) -> Result<()> {
+            if transfer.tip == 0 {
+                return Ok(());
+            }
+            self.ensure_solvent_after(transfer.token, transfer.tip, transfer.tip)?;
+            self.get_erc20_ref(transfer.token)
+                .transfer(to, transfer.tip)?;
+            Ok(())
+        }
+
+        /// Pays the tip of a delivered transfer to `executor`, `Eth` executors have no account
+        /// here so the tip is owed to them until they withdraw it.
+        fn reward_executor(&mut self, transfer: &Transfer, executor: ExecutorId) -> Result<()> {
+            match executor {
+                ExecutorId::Account(account) => self.pay_tip(transfer, account),
+                ExecutorId::Eth(address) => {
+                    let owed = self
+                        .owed_tips
+                        .get((address, transfer.token))
+                        .unwrap_or_default();
+                    self.owed_tips.insert(
+                        (address, transfer.token),
+                        &owed.saturating_add(transfer.tip),
                     );
+                    let total = self.owed_tip_totals.get(transfer.token).unwrap_or_default();
+                    self.owed_tip_totals
+                        .insert(transfer.token, &total.saturating_add(transfer.tip));
+                    Ok(())
                 }
+            }
+        }
+
+        /// Returns the amount and the tip of the transfer to its sender.
+        fn return_to_sender(&self, transfer: &Transfer) -> Result<()> {
+            self.pay_tip(transfer, transfer.from)?;
+            // The tip is already paid but still accounted, so it's settled here as well.
+            self.pay_out(
+                transfer.token,
+                transfer.from,
+                transfer.amount,
+                transfer.amount + transfer.tip,
+            )
+        }
+
+        fn ensure_executor(&self, account: ExecutorId) -> Result<()> {
+            self.executors
+                .contains(&account)
+                .then_some(())
+                .ok_or(Error::ExecutorPermissionDenied)
+        }
+
+        fn ensure_admin(&self, account: AccountId) -> Result<()> {
+            (self.admin == account)
+                .then_some(())
+                .ok_or(Error::AdminPermissionDenied)
+        }
+
+        /// Blake2x256 of the domain separator, chain id, bridge address, nonce and transfer fields.
+        fn global_id(
+            &self,
+            nonce: u128,
+            from: AccountId,
+            token: AccountId,
+            to: [u8; 20],
+            gross_amount: Balance,
+        ) -> Hash {
+            let input = scale::Encode::encode(&(
+                Self::TRANSFER_DOMAIN,
+                self.chain_id,
+                self.env().account_id(),
+                nonce,
+                from,
+                token,
+                to,
+                gross_amount,
+            ));
+            Hash::from(self.env().hash_bytes::<Blake2x256>(&input))
+        }
+
+        /// Blake2x256 of the domain separator, source chain id, `Bridge.sol` address and
+        /// transfer id, so equal ids of different Ethereum deployments don't collide.
+        fn inbound_id(&self, source: EthSource, eth_transfer_id: u128) -> Hash {
+            let input = scale::Encode::encode(&(
+                Self::INBOUND_DOMAIN,
+                source.chain_id,
+                source.bridge,
+                eth_transfer_id,
+            ));
+            Hash::from(self.env().hash_bytes::<Blake2x256>(&input))
+        }
+
+        fn ensure_trusted_source(&self, source: EthSource) -> Result<()> {
+            self.eth_sources
+                .get(source)
+                .ok_or(Error::UnknownSource(source))
+        }
+
+        fn resolve(&self, key: TransferKey) -> Result<u128> {
+            match key {
+                TransferKey::Nonce(transfer_id) => Ok(transfer_id),
+                TransferKey::Global(global_id) => self
+                    .global_ids
+                    .get(global_id)
+                    .ok_or(Error::GlobalIdNotFound(global_id)),
+            }
+        }
+
+        fn find_transfer(&self, transfer_id: u128) -> Result<Transfer> {
+            self.transfers
+                .get(transfer_id)
+                .ok_or(Error::NotFound(transfer_id))
+        }
+
+        /// Stores the transfer and moves it to the list of its current status.
+        fn store_transfer(&mut self, transfer: &Transfer) {
+            let previous = self.transfers.get(transfer.id).map(|stored| stored.status);
+            self.transfers.insert(transfer.id, transfer);
+            if previous == Some(transfer.status) {
+                return;
+            }
+            if let Some(previous) = previous {
+                self.unlist(previous, transfer.id);
+                self.account_outstanding(transfer, previous, false);
+            }
+            self.list(transfer.status, transfer.id);
+            self.account_outstanding(transfer, transfer.status, true);
+        }
 
-                Ok(())
+        fn is_listed(status: TransferStatus) -> bool {
+            matches!(
+                status,
+                TransferStatus::Queued | TransferStatus::InFlight | TransferStatus::Failed
+            )
+        }
+
+        fn list_length(&self, status: TransferStatus) -> u32 {
+            self.status_list_lengths.get(status).unwrap_or_default()
+        }
+
+        fn list(&mut self, status: TransferStatus, transfer_id: u128) {
+            if !Self::is_listed(status) {
+                return;
+            }
+            let length = self.list_length(status);
+            self.status_lists.insert((status, length), &transfer_id);
+            self.status_list_positions.insert(transfer_id, &length);
+            self.status_list_lengths.insert(status, &(length + 1));
+        }
+
+        fn unlist(&mut self, status: TransferStatus, transfer_id: u128) {
+            if !Self::is_listed(status) {
+                return;
+            }
+            let position = match self.status_list_positions.get(transfer_id) {
+                Some(position) => position,
+                None => return,
+            };
+            let last = self.list_length(status) - 1;
+            if position != last {
+                if let Some(last_id) = self.status_lists.get((status, last)) {
+                    self.status_lists.insert((status, position), &last_id);
+                    self.status_list_positions.insert(last_id, &position);
+                }
             }
+            self.status_lists.remove((status, last));
+            self.status_list_positions.remove(transfer_id);
+            self.status_list_lengths.insert(status, &last);
+        }
+
+        /// Returns up to `limit` transfers of the list starting at position `cursor`.
+        fn list_page(&self, status: TransferStatus, cursor: u32, limit: u32) -> Vec<Transfer> {
+            self.index_page(self.list_length(status), cursor, limit, |position| {
+                self.status_lists.get((status, position))
+            })
+        }
+
+        fn index_by_sender(&mut self, sender: AccountId, transfer_id: u128) {
+            let count = self.sender_transfer_counts.get(sender).unwrap_or_default();
+            self.sender_transfers.insert((sender, count), &transfer_id);
+            self.sender_transfer_counts.insert(sender, &(count + 1));
+        }
+
+        fn index_by_destination(&mut self, destination: [u8; 20], transfer_id: u128) {
+            let count = self
+                .destination_transfer_counts
+                .get(destination)
+                .unwrap_or_default();
+            self.destination_transfers
+                .insert((destination, count), &transfer_id);
+            self.destination_transfer_counts
+                .insert(destination, &(count + 1));
+        }
+
+        /// Returns the transfers of up to `limit` ids of an index starting at position `cursor`.
+        fn index_page(
+            &self,
+            count: u32,
+            cursor: u32,
+            limit: u32,
+            id_at: impl Fn(u32) -> Option<u128>,
+        ) -> Vec<Transfer> {
+            let end = cursor
+                .saturating_add(limit.min(Self::MAX_PAGE_SIZE))
+                .min(count);
+            (cursor..end)
+                .filter_map(id_at)
+                .filter_map(|transfer_id| self.transfers.get(transfer_id))
+                .collect()
+        }
+
+        /// Returns a transfer which has just become cancelled to its sender and stores it.
+        fn settle_cancelled(&mut self, transfer: &Transfer) -> Result<()> {
+            self.return_to_sender(transfer)?;
+            self.clear_votes(transfer.id);
+            self.store_transfer(transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Cancelled {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    from: transfer.from,
+                    token: transfer.token,
+                    amount: transfer.amount,
+                    tip: transfer.tip,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Checks the retry policy and moves a failed transfer back to the queue.
+        fn retry(&self, transfer: &mut Transfer) -> Result<()> {
+            let caller = self.env().caller();
+            let now = self.env().block_timestamp();
+            (transfer.from == caller || self.operators.get((transfer.from, caller)).is_some())
+                .then_some(())
+                .ok_or(Error::RetryPermissionDenied)?;
+            let available_at = transfer
+                .status_changed_at()
+                .saturating_add(self.retry_cooldown);
+            transfer.transition(TransferStatus::Queued, now)?;
+            transfer.ensure_before_deadline(now)?;
+            (transfer.retries < self.max_retries)
+                .then_some(())
+                .ok_or(Error::TooManyRetries {
+                    id: transfer.id,
+                    max_retries: self.max_retries,
+                })?;
+            (now >= available_at)
+                .then_some(())
+                .ok_or(Error::RetryTooEarly { available_at })?;
+            transfer.retries += 1;
+            Ok(())
+        }
+
+        fn ensure_amount_within_limits(&self, token: AccountId, amount: Balance) -> Result<()> {
+            let limits = self.amount_limits.get(token).unwrap_or_default();
+            (amount >= limits.min_amount)
+                .then_some(())
+                .ok_or(Error::BelowMinimum {
+                    amount,
+                    min_amount: limits.min_amount,
+                })?;
+            match limits.max_amount {
+                Some(max_amount) if amount > max_amount => {
+                    Err(Error::AboveMaximum { amount, max_amount })
+                }
+                _ => Ok(()),
+            }
+        }
+
+        /// Returns the volume window with `amount` accounted, it's up to the caller to store it.
+        fn volume_after(
+            &self,
+            token: AccountId,
+            direction: Direction,
+            amount: Balance,
+        ) -> Result<Option<VolumeWindow>> {
+            let rate_limit = match self.rate_limits.get((token, direction)) {
+                Some(rate_limit) => rate_limit,
+                None => return Ok(None),
+            };
+            let now = self.env().block_timestamp();
+            let volume = self
+                .volumes
+                .get((token, direction))
+                .unwrap_or_default()
+                .advance(now, rate_limit.window);
+            let remaining = rate_limit
+                .cap
+                .saturating_sub(volume.usage(now, rate_limit.window));
+            (amount <= remaining)
+                .then_some(())
+                .ok_or(Error::RateLimitExceeded {
+                    direction,
+                    amount,
+                    remaining,
+                })?;
+            Ok(Some(VolumeWindow {
+                used: volume.used + amount,
+                ..volume
+            }))
+        }
+
+        /// State can't change until the storage is migrated after an upgrade, only `set_code`
+        /// and `migrate` itself are available.
+        fn ensure_migrated(&self) -> Result<()> {
+            (self.storage_version == Self::STORAGE_VERSION)
+                .then_some(())
+                .ok_or(Error::MigrationPending(self.storage_version))
+        }
+
+        fn ensure_valid_threshold(&self, threshold: u32, executors: u32) -> Result<()> {
+            (threshold > 0 && threshold <= executors)
+                .then_some(())
+                .ok_or(Error::InvalidThreshold {
+                    threshold,
+                    executors,
+                })
+        }
+
+        fn ensure_not_paused(&self, operation: Operation) -> Result<()> {
+            (!self.paused.get(operation).unwrap_or_default())
+                .then_some(())
+                .ok_or(Error::Paused(operation))
+        }
+
+        fn vote_of(&self, transfer_id: u128, executor: ExecutorId) -> Option<Outcome> {
+            self.votes
+                .get((transfer_id, executor))
+                .filter(|(epoch, _)| *epoch == self.executors_epoch)
+                .map(|(_, vote)| vote)
+        }
+
+        fn vote_count(&self, transfer_id: u128, outcome: &Outcome) -> u32 {
+            self.vote_counts
+                .get((transfer_id, outcome))
+                .filter(|(epoch, _)| *epoch == self.executors_epoch)
+                .map(|(_, count)| count)
+                .unwrap_or_default()
+        }
+
+        fn release_vote_of(&self, inbound_id: Hash, executor: ExecutorId) -> Option<ReleaseVote> {
+            self.release_votes
+                .get((inbound_id, executor))
+                .filter(|(epoch, _)| *epoch == self.executors_epoch)
+                .map(|(_, vote)| vote)
+        }
+
+        fn release_vote_count(&self, inbound_id: Hash, vote: ReleaseVote) -> u32 {
+            self.release_vote_counts
+                .get((inbound_id, vote))
+                .filter(|(epoch, _)| *epoch == self.executors_epoch)
+                .map(|(_, count)| count)
+                .unwrap_or_default()
+        }
+
+        fn clear_votes(&mut self, transfer_id: u128) {
+            for executor in self.executors.iter() {
+                if let Some((_, outcome)) = self.votes.get((transfer_id, *executor)) {
+                    self.vote_counts.remove((transfer_id, outcome));
+                    self.votes.remove((transfer_id, *executor));
+                }
+            }
+        }
+
+        fn clear_release_votes(&mut self, inbound_id: Hash) {
+            for executor in self.executors.iter() {
+                if let Some((_, vote)) = self.release_votes.get((inbound_id, *executor)) {
+                    self.release_vote_counts.remove((inbound_id, vote));
+                    self.release_votes.remove((inbound_id, *executor));
+                }
+            }
+        }
+
+        /// Keccak256 of the domain separator followed by the SCALE encoded bridge address and `payload`.
+        fn digest(&self, domain: &[u8], payload: impl scale::Encode) -> [u8; 32] {
+            let mut input = domain.to_vec();
+            scale::Encode::encode_to(&(self.env().account_id(), payload), &mut input);
+            self.env().hash_bytes::<Keccak256>(&input)
+        }
+
+        /// Every attempt of a transfer is signed separately, `retries` tells them apart.
+        fn process_digest(&self, transfer: &Transfer, outcome: &Outcome) -> [u8; 32] {
+            self.digest(
+                Self::PROCESS_DOMAIN,
+                (transfer.id, transfer.retries, outcome),
+            )
+        }
+
+        fn tips_digest(&self, executor: [u8; 20], token: AccountId, to: AccountId) -> [u8; 32] {
+            let nonce = self.tip_withdrawal_nonces.get(executor).unwrap_or_default();
+            self.digest(Self::TIPS_DOMAIN, (executor, token, to, nonce))
+        }
+
+        fn claim_digest(
+            &self,
+            transfer: &Transfer,
+            lease_duration: Timestamp,
+            valid_until: Timestamp,
+        ) -> [u8; 32] {
+            self.digest(
+                Self::CLAIM_DOMAIN,
+                (transfer.id, transfer.retries, lease_duration, valid_until),
+            )
+        }
+
+        /// Recovers the Ethereum address which signed `digest` as an `eth_sign` message.
+        fn recover_signer(
+            &self,
+            digest: [u8; 32],
+            signature: &[u8; 65],
+            index: u32,
+        ) -> Result<[u8; 20]> {
+            let mut message = b"\x19Ethereum Signed Message:\n32".to_vec();
+            message.extend_from_slice(&digest);
+            let message_hash = self.env().hash_bytes::<Keccak256>(&message);
+            self.env()
+                .ecdsa_recover(signature, &message_hash)
+                .and_then(|public_key| self.env().ecdsa_to_eth_address(&public_key))
+                .map_err(|_| Error::InvalidSignature(index))
+        }
+
+        /// Recovers the Ethereum addresses which signed `digest` as an `eth_sign` message
+        /// and checks that every one of them is a distinct executor.
+        fn recover_signers(
+            &self,
+            digest: [u8; 32],
+            signatures: &[[u8; 65]],
+        ) -> Result<Vec<ExecutorId>> {
+            let mut signers: Vec<ExecutorId> = Vec::with_capacity(signatures.len());
+            for (index, signature) in signatures.iter().enumerate() {
+                let address = self.recover_signer(digest, signature, index as u32)?;
+                let signer = ExecutorId::Eth(address);
+                (!signers.contains(&signer))
+                    .then_some(())
+                    .ok_or(Error::DuplicateSignature(address))?;
+                self.executors
+                    .contains(&signer)
+                    .then_some(())
+                    .ok_or(Error::UnknownExecutor(signer))?;
+                signers.push(signer);
+            }
+            Ok(signers)
+        }
+
+        /// Leases a queued transfer, or one whose lease has expired, to `holder`.
+        fn grant_lease(
+            &mut self,
+            transfer_id: u128,
+            holder: ExecutorId,
+            lease_duration: Timestamp,
+        ) -> Result<()> {
+            (lease_duration > 0)
+                .then_some(())
+                .ok_or(Error::InvalidLeaseDuration)?;
+            let now = self.env().block_timestamp();
+            let mut transfer = self.find_transfer(transfer_id)?;
+            if let Some(lease) = transfer.active_lease(now) {
+                return Err(Error::LeaseHeld {
+                    id: transfer_id,
+                    holder: lease.holder,
+                    expires_at: lease.expires_at,
+                });
+            }
+            // An expired lease is taken over without a status change.
+            if transfer.status != TransferStatus::InFlight {
+                transfer.transition(TransferStatus::InFlight, now)?;
+            }
+            transfer.ensure_before_deadline(now)?;
+            let expires_at = now
+                .saturating_add(lease_duration)
+                .min(transfer.deadline.unwrap_or(Timestamp::MAX));
+            transfer.lease = Some(Lease { holder, expires_at });
+            self.store_transfer(&transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Claimed {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    executor: holder,
+                    expires_at,
+                    timestamp: now,
+                },
+            );
+            Ok(())
+        }
+
+        /// Counts the `executor` vote for the outcome of a queued transfer, returns
+        /// whether it finalized the transfer.
+        fn vote_transfer(
+            &mut self,
+            transfer_id: u128,
+            executor: ExecutorId,
+            outcome: Outcome,
+        ) -> Result<bool> {
+            let mut transfer = self.find_transfer(transfer_id)?;
+            let first_vote = self
+                .executors
+                .iter()
+                .all(|executor| self.vote_of(transfer_id, *executor).is_none());
+            if transfer.status == TransferStatus::InFlight && first_vote {
+                // The lease holder reports the outcome of a claimed transfer, any executor
+                // can add its vote afterwards.
+                transfer
+                    .active_lease(self.env().block_timestamp())
+                    .filter(|lease| lease.holder == executor)
+                    .ok_or(Error::LeasePermissionDenied(transfer_id))?;
+            }
+            let status = match &outcome {
+                Outcome::Succeeded(_) => TransferStatus::Succeeded,
+                Outcome::Failed(failure) => {
+                    let len = failure.detail.len() as u32;
+                    (len <= Self::MAX_FAILURE_DETAIL_LEN).then_some(()).ok_or(
+                        Error::FailureDetailTooLong {
+                            len,
+                            max: Self::MAX_FAILURE_DETAIL_LEN,
+                        },
+                    )?;
+                    TransferStatus::Failed
+                }
+            };
+            // Validates the transition before any vote is counted, it's stored on finalization only.
+            transfer.transition(status, self.env().block_timestamp())?;
+            if status == TransferStatus::Succeeded {
+                transfer.ensure_before_deadline(self.env().block_timestamp())?;
+            }
+            match self.vote_of(transfer_id, executor) {
+                Some(vote) if vote == outcome => return Err(Error::AlreadyVoted(transfer_id)),
+                Some(_) => return Err(Error::ConflictingVote(transfer_id)),
+                None => {}
+            }
+            let votes = self.vote_count(transfer_id, &outcome) + 1;
+            let conflicting_votes = self
+                .executors
+                .iter()
+                .filter(|executor| {
+                    matches!(self.vote_of(transfer_id, **executor), Some(vote) if vote != outcome)
+                })
+                .count() as u32;
+            let finalized = votes >= self.threshold;
+            // The lease holder delivered the transfer, whoever submits the finalizing vote.
+            let tip_recipient = transfer.lease.map(|lease| lease.holder).unwrap_or(executor);
+            if finalized {
+                if let Outcome::Succeeded(_) = outcome {
+                    self.reward_executor(&transfer, tip_recipient)?;
+                }
+            }
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Voted {
+                    id: transfer_id,
+                    executor,
+                    outcome: outcome.clone(),
+                    votes,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            if conflicting_votes > 0 {
+                ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                    self.env(),
+                    ConflictingVotes {
+                        id: transfer_id,
+                        votes,
+                        conflicting_votes,
+                        timestamp: self.env().block_timestamp(),
+                    },
+                );
+            }
+            if !finalized {
+                self.votes.insert(
+                    (transfer_id, executor),
+                    &(self.executors_epoch, outcome.clone()),
+                );
+                self.vote_counts
+                    .insert((transfer_id, &outcome), &(self.executors_epoch, votes));
+                return Ok(false);
+            }
+            self.clear_votes(transfer_id);
+            match outcome {
+                Outcome::Succeeded(settlement) => {
+                    transfer.settlement = Some(settlement);
+                    self.store_transfer(&transfer);
+                    ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                        self.env(),
+                        SuccessfulTransfer {
+                            id: transfer.id,
+                            global_id: transfer.global_id,
+                            from: transfer.from,
+                            token: transfer.token,
+                            to: transfer.to,
+                            amount: transfer.amount,
+                            settlement,
+                            tip: transfer.tip,
+                            tip_recipient,
+                            timestamp: self.env().block_timestamp(),
+                        },
+                    );
+                }
+                Outcome::Failed(failure) => {
+                    transfer.failure = Some(failure.clone());
+                    self.store_transfer(&transfer);
+                    ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                        self.env(),
+                        FailedTransfer {
+                            id: transfer.id,
+                            global_id: transfer.global_id,
+                            from: transfer.from,
+                            token: transfer.token,
+                            to: transfer.to,
+                            amount: transfer.amount,
+                            tip: transfer.tip,
+                            failure,
+                            timestamp: self.env().block_timestamp(),
+                        },
+                    );
+                }
+            }
+            Ok(true)
+        }
+
+        /// Counts the `executor` vote for an inbound payout, returns whether it released the transfer.
+        fn vote_release(
+            &mut self,
+            source: EthSource,
+            eth_transfer_id: u128,
+            executor: ExecutorId,
+            vote: ReleaseVote,
+        ) -> Result<bool> {
+            let (token, to, amount) = vote;
+            self.ensure_trusted_source(source)?;
+            let inbound_id = self.inbound_id(source, eth_transfer_id);
+            self.ensure_registered(token)?;
+            (amount > 0).then_some(()).ok_or(Error::ZeroAmount)?;
+            self.released_transfers
+                .get(inbound_id)
+                .is_none()
+                .then_some(())
+                .ok_or(Error::AlreadyReleased(inbound_id))?;
+            match self.release_vote_of(inbound_id, executor) {
+                Some(previous) if previous == vote => {
+                    return Err(Error::AlreadyVoted(eth_transfer_id))
+                }
+                Some(_) => return Err(Error::ConflictingVote(eth_transfer_id)),
+                None => {}
+            }
+            let votes = self.release_vote_count(inbound_id, vote) + 1;
+            let finalized = votes >= self.threshold;
+            let volume = if finalized {
+                let volume = self.volume_after(token, Direction::Inbound, amount)?;
+                self.pay_out(token, to, amount, 0)?;
+                volume
+            } else {
+                None
+            };
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ReleaseVoted {
+                    inbound_id,
+                    source,
+                    eth_transfer_id,
+                    executor,
+                    token,
+                    to,
+                    amount,
+                    votes,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            if !finalized {
+                self.release_votes
+                    .insert((inbound_id, executor), &(self.executors_epoch, vote));
+                self.release_vote_counts
+                    .insert((inbound_id, vote), &(self.executors_epoch, votes));
+                return Ok(false);
+            }
+            if let Some(volume) = volume {
+                self.volumes.insert((token, Direction::Inbound), &volume);
+            }
+            self.clear_release_votes(inbound_id);
+            self.released_transfers.insert(inbound_id, &());
+            let released = self.released_amounts.get(token).unwrap_or_default();
+            self.released_amounts.insert(token, &(released + amount));
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Released {
+                    inbound_id,
+                    source,
+                    eth_transfer_id,
+                    to,
+                    token,
+                    amount,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(true)
+        }
+
+        #[ink(constructor)]
+        pub fn new(chain_id: u64) -> Self {
+            Self::new_with_executors(
+                chain_id,
+                ink_prelude::vec![ExecutorId::Account(Self::env().caller())],
+                1,
+            )
+        }
+
+        /// Creates a bridge on chain `chain_id` whose transfers are finalized once `threshold`
+        /// distinct `executors` have voted for the same outcome.
+        #[ink(constructor)]
+        pub fn new_with_executors(
+            chain_id: u64,
+            executors: Vec<ExecutorId>,
+            threshold: u32,
+        ) -> Self {
+            assert!(
+                threshold > 0 && threshold as usize <= executors.len(),
+                "threshold must be between 1 and the number of executors"
+            );
+            assert!(
+                executors
+                    .iter()
+                    .enumerate()
+                    .all(|(i, executor)| !executors[..i].contains(executor)),
+                "executors must be distinct"
+            );
+            ink_lang::utils::initialize_contract(|contract: &mut Self| {
+                contract.admin = Self::env().caller();
+                contract.pauser = Self::env().caller();
+                contract.executors = executors;
+                contract.threshold = threshold;
+                contract.reclaim_timeout = Self::DEFAULT_RECLAIM_TIMEOUT;
+                contract.cancel_window = Self::DEFAULT_CANCEL_WINDOW;
+                contract.max_retries = Self::DEFAULT_MAX_RETRIES;
+                contract.retry_cooldown = Self::DEFAULT_RETRY_COOLDOWN;
+                contract.counter = 0;
+                contract.storage_version = Self::STORAGE_VERSION;
+                contract.chain_id = chain_id;
+            })
+        }
+
+        /// Looks a transfer up by its nonce or global id, `None` if no transfer has the key.
+        #[ink(message)]
+        pub fn get_transfer(&self, key: TransferKey) -> Result<Option<Transfer>> {
+            match self.resolve(key) {
+                Ok(transfer_id) => Ok(self.transfers.get(transfer_id)),
+                Err(Error::GlobalIdNotFound(_)) => Ok(None),
+                Err(error) => Err(error),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_chain_id(&self) -> Result<u64> {
+            Ok(self.chain_id)
+        }
+
+        /// Lists queued transfers starting at position `cursor`, at most 100 per call.
+        ///
+        /// Positions are not stable: removing a transfer moves the last one into its place,
+        /// so a full scan should be repeated until `get_queue_depth` matches what was seen.
+        #[ink(message)]
+        pub fn list_queued(&self, cursor: u32, limit: u32) -> Result<Vec<Transfer>> {
+            Ok(self.list_page(TransferStatus::Queued, cursor, limit))
+        }
+
+        /// Lists claimed transfers the same way as `list_queued`.
+        #[ink(message)]
+        pub fn list_in_flight(&self, cursor: u32, limit: u32) -> Result<Vec<Transfer>> {
+            Ok(self.list_page(TransferStatus::InFlight, cursor, limit))
+        }
+
+        #[ink(message)]
+        pub fn get_in_flight_count(&self) -> Result<u32> {
+            Ok(self.list_length(TransferStatus::InFlight))
+        }
+
+        /// Lists failed transfers the same way as `list_queued`.
+        #[ink(message)]
+        pub fn list_failed(&self, cursor: u32, limit: u32) -> Result<Vec<Transfer>> {
+            Ok(self.list_page(TransferStatus::Failed, cursor, limit))
+        }
+
+        #[ink(message)]
+        pub fn get_queue_depth(&self) -> Result<u32> {
+            Ok(self.list_length(TransferStatus::Queued))
+        }
+
+        #[ink(message)]
+        pub fn get_failed_count(&self) -> Result<u32> {
+            Ok(self.list_length(TransferStatus::Failed))
+        }
+
+        /// Lists the transfers made by `sender` with their current status, oldest first,
+        /// starting at position `cursor` and at most 100 per call.
+        #[ink(message)]
+        pub fn list_by_sender(
+            &self,
+            sender: AccountId,
+            cursor: u32,
+            limit: u32,
+        ) -> Result<Vec<Transfer>> {
+            let count = self.sender_transfer_counts.get(sender).unwrap_or_default();
+            Ok(self.index_page(count, cursor, limit, |position| {
+                self.sender_transfers.get((sender, position))
+            }))
+        }
+
+        #[ink(message)]
+        pub fn get_sender_transfer_count(&self, sender: AccountId) -> Result<u32> {
+            Ok(self.sender_transfer_counts.get(sender).unwrap_or_default())
+        }
+
+        /// Lists the transfers sent to the Ethereum `destination` the same way as `list_by_sender`.
+        #[ink(message)]
+        pub fn list_by_destination(
+            &self,
+            destination: [u8; 20],
+            cursor: u32,
+            limit: u32,
+        ) -> Result<Vec<Transfer>> {
+            let count = self
+                .destination_transfer_counts
+                .get(destination)
+                .unwrap_or_default();
+            Ok(self.index_page(count, cursor, limit, |position| {
+                self.destination_transfers.get((destination, position))
+            }))
+        }
+
+        #[ink(message)]
+        pub fn get_destination_transfer_count(&self, destination: [u8; 20]) -> Result<u32> {
+            Ok(self
+                .destination_transfer_counts
+                .get(destination)
+                .unwrap_or_default())
+        }
+
+        /// Where the transfer was delivered, `None` if it's unknown or wasn't delivered yet.
+        #[ink(message)]
+        pub fn get_settlement(&self, key: TransferKey) -> Result<Option<Settlement>> {
+            Ok(self
+                .get_transfer(key)?
+                .and_then(|transfer| transfer.settlement))
+        }
+
+        #[ink(message)]
+        pub fn get_token(&self, token: AccountId) -> Result<Option<TokenInfo>> {
+            Ok(self.tokens.get(token))
+        }
+
+        /// Adds `token` to the registry or updates its Ethereum counterpart, decimals and mode.
+        ///
+        /// For `TokenMode::MintBurn` the bridge has to be made the minter of `token`. The mode
+        /// can't change while queued, in-flight or failed transfers of `token` are outstanding.
+        #[ink(message)]
+        pub fn register_token(
+            &mut self,
+            token: AccountId,
+            eth_address: [u8; 20],
+            decimals: u8,
+            mode: TokenMode,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            if let Some(info) = self.tokens.get(token) {
+                let outstanding = [
+                    &self.queued_amounts,
+                    &self.refundable_amounts,
+                    &self.locked_tips,
+                ]
+                .iter()
+                .any(|amounts| amounts.get(token).unwrap_or_default() > 0);
+                (info.mode == mode || !outstanding)
+                    .then_some(())
+                    .ok_or(Error::TokenModeLocked(token))?;
+            }
+            self.tokens.insert(
+                token,
+                &TokenInfo {
+                    eth_address,
+                    decimals,
+                    mode,
+                    enabled: true,
+                },
+            );
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                TokenRegistered {
+                    token,
+                    eth_address,
+                    decimals,
+                    mode,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Stops new transfers and releases of `token`, already queued transfers can still be refunded.
+        #[ink(message)]
+        pub fn unregister_token(&mut self, token: AccountId) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            let info = self.ensure_registered(token)?;
+            self.tokens.insert(
+                token,
+                &TokenInfo {
+                    enabled: false,
+                    ..info
+                },
+            );
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                TokenUnregistered {
+                    token,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_trusted_source(&self, source: EthSource) -> Result<bool> {
+            Ok(self.eth_sources.get(source).is_some())
+        }
+
+        /// Trusts or distrusts the Ethereum chain and `Bridge.sol` deployment `source`,
+        /// `release` only pays out transfers of trusted sources.
+        #[ink(message)]
+        pub fn set_eth_source(&mut self, source: EthSource, trusted: bool) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            if trusted {
+                self.eth_sources.insert(source, &());
+            } else {
+                self.eth_sources.remove(source);
+            }
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                EthSourceChanged {
+                    source,
+                    trusted,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_fee_schedule(&self, token: AccountId) -> Result<FeeSchedule> {
+            Ok(self.fees.get(token).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_collected_fees(&self, token: AccountId) -> Result<Balance> {
+            Ok(self.collected_fees.get(token).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn set_fee_schedule(
+            &mut self,
+            token: AccountId,
+            flat: Balance,
+            basis_points: u16,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.ensure_registered(token)?;
+            (basis_points <= FeeSchedule::MAX_BASIS_POINTS)
+                .then_some(())
+                .ok_or(Error::InvalidFee(basis_points))?;
+            self.fees.insert(token, &FeeSchedule { flat, basis_points });
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                FeeScheduleChanged {
+                    token,
+                    flat,
+                    basis_points,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Sends all fees collected in `token` to `to`.
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, token: AccountId, to: AccountId) -> Result<Balance> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            let amount = self.collected_fees.get(token).unwrap_or_default();
+            (amount > 0).then_some(()).ok_or(Error::ZeroAmount)?;
+            self.ensure_solvent_after(token, amount, amount)?;
+            self.get_erc20_ref(token).transfer(to, amount)?;
+            self.collected_fees.remove(token);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                FeesWithdrawn {
+                    token,
+                    to,
+                    amount,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        pub fn get_owed_tips(&self, executor: [u8; 20], token: AccountId) -> Result<Balance> {
+            Ok(self.owed_tips.get((executor, token)).unwrap_or_default())
+        }
+
+        /// Digest the `Eth` `executor` signs with `eth_sign` to withdraw its tips in `token`
+        /// to `to` with `withdraw_tips_signed`, it's valid for a single withdrawal.
+        #[ink(message)]
+        pub fn get_tips_digest(
+            &self,
+            executor: [u8; 20],
+            token: AccountId,
+            to: AccountId,
+        ) -> Result<[u8; 32]> {
+            Ok(self.tips_digest(executor, token, to))
+        }
+
+        /// Sends all tips `executor` earned in `token` to `to`, anyone can submit it with the
+        /// signature of `get_tips_digest`. Former executors can still withdraw their tips.
+        #[ink(message)]
+        pub fn withdraw_tips_signed(
+            &mut self,
+            executor: [u8; 20],
+            token: AccountId,
+            to: AccountId,
+            signature: [u8; 65],
+        ) -> Result<Balance> {
+            self.ensure_migrated()?;
+            let digest = self.tips_digest(executor, token, to);
+            (self.recover_signer(digest, &signature, 0)? == executor)
+                .then_some(())
+                .ok_or(Error::InvalidSignature(0))?;
+            let amount = self.owed_tips.get((executor, token)).unwrap_or_default();
+            (amount > 0).then_some(()).ok_or(Error::ZeroAmount)?;
+            self.ensure_solvent_after(token, amount, amount)?;
+            self.get_erc20_ref(token).transfer(to, amount)?;
+            self.owed_tips.remove((executor, token));
+            let total = self.owed_tip_totals.get(token).unwrap_or_default();
+            self.owed_tip_totals
+                .insert(token, &total.saturating_sub(amount));
+            let nonce = self.tip_withdrawal_nonces.get(executor).unwrap_or_default();
+            self.tip_withdrawal_nonces.insert(executor, &(nonce + 1));
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                TipsWithdrawn {
+                    executor,
+                    token,
+                    to,
+                    amount,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(amount)
+        }
+
+        /// Compares the bridge balance of `token` with the amounts it owes.
+        #[ink(message)]
+        pub fn solvency(&self, token: AccountId) -> Result<Solvency> {
+            self.tokens
+                .get(token)
+                .ok_or(Error::UnsupportedToken(token))?;
+            let balance = self
+                .get_erc20_ref(token)
+                .balance_of(self.env().account_id());
+            let liabilities = self.liabilities(token);
+            Ok(Solvency {
+                balance,
+                queued: self.queued_amounts.get(token).unwrap_or_default(),
+                refundable: self.refundable_amounts.get(token).unwrap_or_default(),
+                collected_fees: self.collected_fees.get(token).unwrap_or_default(),
+                tips: self.locked_tips.get(token).unwrap_or_default(),
+                owed_tips: self.owed_tip_totals.get(token).unwrap_or_default(),
+                released: self.released_amounts.get(token).unwrap_or_default(),
+                liabilities,
+                surplus: balance.saturating_sub(liabilities),
+                deficit: liabilities.saturating_sub(balance),
+            })
+        }
+
+        #[ink(message)]
+        pub fn get_amount_limits(&self, token: AccountId) -> Result<AmountLimits> {
+            Ok(self.amount_limits.get(token).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn get_rate_limit(
+            &self,
+            token: AccountId,
+            direction: Direction,
+        ) -> Result<Option<RateLimit>> {
+            Ok(self.rate_limits.get((token, direction)))
+        }
+
+        /// Returns the volume bridged within the last rate limit window and the capacity left.
+        #[ink(message)]
+        pub fn get_volume_usage(
+            &self,
+            token: AccountId,
+            direction: Direction,
+        ) -> Result<VolumeUsage> {
+            let rate_limit = match self.rate_limits.get((token, direction)) {
+                Some(rate_limit) => rate_limit,
+                None => {
+                    return Ok(VolumeUsage {
+                        used: 0,
+                        cap: None,
+                        remaining: None,
+                    })
+                }
+            };
+            let now = self.env().block_timestamp();
+            let used = self
+                .volumes
+                .get((token, direction))
+                .unwrap_or_default()
+                .advance(now, rate_limit.window)
+                .usage(now, rate_limit.window);
+            Ok(VolumeUsage {
+                used,
+                cap: Some(rate_limit.cap),
+                remaining: Some(rate_limit.cap.saturating_sub(used)),
+            })
+        }
+
+        #[ink(message)]
+        pub fn set_amount_limits(
+            &mut self,
+            token: AccountId,
+            min_amount: Balance,
+            max_amount: Option<Balance>,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.ensure_registered(token)?;
+            self.amount_limits.insert(
+                token,
+                &AmountLimits {
+                    min_amount,
+                    max_amount,
+                },
+            );
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                AmountLimitsChanged {
+                    token,
+                    min_amount,
+                    max_amount,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Sets the rolling volume cap of `token` in `direction`, `None` removes the cap.
+        #[ink(message)]
+        pub fn set_rate_limit(
+            &mut self,
+            token: AccountId,
+            direction: Direction,
+            rate_limit: Option<RateLimit>,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.ensure_registered(token)?;
+            match rate_limit {
+                Some(RateLimit { window: 0, .. }) => return Err(Error::InvalidRateLimitWindow),
+                Some(rate_limit) => {
+                    self.rate_limits.insert((token, direction), &rate_limit);
+                }
+                None => self.rate_limits.remove((token, direction)),
+            }
+            self.volumes.remove((token, direction));
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                RateLimitChanged {
+                    token,
+                    direction,
+                    rate_limit,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_reclaim_timeout(&self) -> Result<Timestamp> {
+            Ok(self.reclaim_timeout)
+        }
+
+        #[ink(message)]
+        pub fn set_reclaim_timeout(&mut self, timeout: Timestamp) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.reclaim_timeout = timeout;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ReclaimTimeoutChanged {
+                    timeout,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_cancel_window(&self) -> Result<Timestamp> {
+            Ok(self.cancel_window)
+        }
+
+        #[ink(message)]
+        pub fn set_cancel_window(&mut self, window: Timestamp) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.cancel_window = window;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                CancelWindowChanged {
+                    window,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_retry_policy(&self) -> Result<RetryPolicy> {
+            Ok(RetryPolicy {
+                max_retries: self.max_retries,
+                cooldown: self.retry_cooldown,
+            })
+        }
+
+        #[ink(message)]
+        pub fn set_retry_policy(&mut self, policy: RetryPolicy) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.max_retries = policy.max_retries;
+            self.retry_cooldown = policy.cooldown;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                RetryPolicyChanged {
+                    policy,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_operator(&self, owner: AccountId, operator: AccountId) -> Result<bool> {
+            Ok(self.operators.get((owner, operator)).is_some())
+        }
+
+        /// Allows or disallows `operator` to try again failed transfers of the caller.
+        #[ink(message)]
+        pub fn set_operator(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            self.ensure_migrated()?;
+            let owner = self.env().caller();
+            if approved {
+                self.operators.insert((owner, operator), &());
+            } else {
+                self.operators.remove((owner, operator));
+            }
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                OperatorApproval {
+                    owner,
+                    operator,
+                    approved,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_executors(&self) -> Result<Vec<ExecutorId>> {
+            Ok(self.executors.clone())
+        }
+
+        #[ink(message)]
+        pub fn get_threshold(&self) -> Result<u32> {
+            Ok(self.threshold)
+        }
+
+        #[ink(message)]
+        pub fn get_admin(&self) -> Result<AccountId> {
+            Ok(self.admin)
+        }
+
+        #[ink(message)]
+        pub fn get_pending_admin(&self) -> Result<Option<AccountId>> {
+            Ok(self.pending_admin)
+        }
+
+        #[ink(message)]
+        pub fn get_pauser(&self) -> Result<AccountId> {
+            Ok(self.pauser)
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self, operation: Operation) -> Result<bool> {
+            Ok(self.paused.get(operation).unwrap_or_default())
+        }
+
+        #[ink(message)]
+        pub fn set_pauser(&mut self, pauser: AccountId) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            let previous = self.pauser;
+            self.pauser = pauser;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                PauserChanged {
+                    previous,
+                    pauser,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Blocks the given `operations` until they are unpaused.
+        #[ink(message)]
+        pub fn pause(&mut self, operations: Vec<Operation>) -> Result<()> {
+            self.ensure_migrated()?;
+            (self.pauser == self.env().caller())
+                .then_some(())
+                .ok_or(Error::PauserPermissionDenied)?;
+            for operation in operations {
+                self.paused.insert(operation, &true);
+                ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                    self.env(),
+                    Paused {
+                        operation,
+                        timestamp: self.env().block_timestamp(),
+                    },
+                );
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self, operations: Vec<Operation>) -> Result<()> {
+            self.ensure_migrated()?;
+            (self.pauser == self.env().caller())
+                .then_some(())
+                .ok_or(Error::PauserPermissionDenied)?;
+            for operation in operations {
+                self.paused.remove(operation);
+                ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                    self.env(),
+                    Unpaused {
+                        operation,
+                        timestamp: self.env().block_timestamp(),
+                    },
+                );
+            }
+            Ok(())
+        }
+
+        /// Replaces the `previous` executor key with `executor`.
+        ///
+        /// Votes which are not finalized yet are discarded.
+        #[ink(message)]
+        pub fn set_executor(&mut self, previous: ExecutorId, executor: ExecutorId) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            (!self.executors.contains(&executor))
+                .then_some(())
+                .ok_or(Error::DuplicateExecutor(executor))?;
+            let position = self
+                .executors
+                .iter()
+                .position(|id| *id == previous)
+                .ok_or(Error::UnknownExecutor(previous))?;
+            self.executors[position] = executor;
+            self.executors_epoch += 1;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ExecutorChanged {
+                    previous,
+                    executor,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Adds `executor` to the set, the threshold stays the same.
+        ///
+        /// Votes which are not finalized yet are discarded.
+        #[ink(message)]
+        pub fn add_executor(&mut self, executor: ExecutorId) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            (!self.executors.contains(&executor))
+                .then_some(())
+                .ok_or(Error::DuplicateExecutor(executor))?;
+            self.executors.push(executor);
+            self.executors_epoch += 1;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ExecutorAdded {
+                    executor,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Removes `executor` from the set, the threshold has to stay within the remaining set.
+        ///
+        /// Votes which are not finalized yet are discarded.
+        #[ink(message)]
+        pub fn remove_executor(&mut self, executor: ExecutorId) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            let position = self
+                .executors
+                .iter()
+                .position(|id| *id == executor)
+                .ok_or(Error::UnknownExecutor(executor))?;
+            self.ensure_valid_threshold(self.threshold, self.executors.len() as u32 - 1)?;
+            self.executors.swap_remove(position);
+            self.executors_epoch += 1;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ExecutorRemoved {
+                    executor,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Sets the number of matching votes which finalize a transfer or a release.
+        ///
+        /// Votes which are not finalized yet are discarded.
+        #[ink(message)]
+        pub fn set_threshold(&mut self, threshold: u32) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_admin(self.env().caller())?;
+            self.ensure_valid_threshold(threshold, self.executors.len() as u32)?;
+            let previous = self.threshold;
+            self.threshold = threshold;
+            self.executors_epoch += 1;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                ThresholdChanged {
+                    previous,
+                    threshold,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Starts the ownership transfer, `new_admin` has to accept it with `accept_ownership`.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_admin: AccountId) -> Result<()> {
+            self.ensure_migrated()?;
+            let caller = self.env().caller();
+            self.ensure_admin(caller)?;
+            self.pending_admin = Some(new_admin);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                OwnershipTransferStarted {
+                    admin: caller,
+                    pending_admin: new_admin,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            self.ensure_migrated()?;
+            let caller = self.env().caller();
+            (self.pending_admin == Some(caller))
+                .then_some(())
+                .ok_or(Error::PendingAdminPermissionDenied)?;
+            let previous_admin = self.admin;
+            self.admin = caller;
+            self.pending_admin = None;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                OwnershipTransferred {
+                    previous_admin,
+                    admin: caller,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_storage_version(&self) -> Result<u32> {
+            Ok(self.storage_version)
+        }
+
+        /// Replaces the contract code with the uploaded `code_hash`, keeping the storage and balances.
+        ///
+        /// Bridge operations are unavailable until the admin calls `migrate` on the new code.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) -> Result<()> {
+            self.ensure_admin(self.env().caller())?;
+            let old_code_hash = self.env().own_code_hash().map_err(|_| Error::Unexpected)?;
+            ink_env::set_code_hash(&code_hash)
+                .map_err(|_| Error::CodeUpgradeFailed(Hash::from(code_hash)))?;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Upgraded {
+                    old_code_hash,
+                    new_code_hash: Hash::from(code_hash),
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Brings the storage written by the previous code up to `STORAGE_VERSION`, runs once per upgrade.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<()> {
+            self.ensure_admin(self.env().caller())?;
+            let from_version = self.storage_version;
+            (from_version < Self::STORAGE_VERSION)
+                .then_some(())
+                .ok_or(Error::AlreadyMigrated(from_version))?;
+            // Storage changes introduced by every version after `from_version` go here, in order.
+            self.storage_version = Self::STORAGE_VERSION;
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Migrated {
+                    from_version,
+                    to_version: Self::STORAGE_VERSION,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Locks `amount` of `token` and queues its delivery to `external_destination_address`.
+        ///
+        /// The fee is deducted from `amount` and is not returned on refund. The optional `tip`
+        /// is locked on top of `amount` and paid to the executor which claims and delivers it.
+        /// Once the optional `deadline` passes the transfer can't be delivered, see `expire`.
+        #[ink(message)]
+        pub fn transfer(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+            external_destination_address: [u8; 20],
+            tip: Option<Balance>,
+            deadline: Option<Timestamp>,
+        ) -> Result<u128> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Deposit)?;
+            match deadline {
+                Some(deadline) if deadline <= self.env().block_timestamp() => {
+                    return Err(Error::InvalidDeadline(deadline))
+                }
+                _ => {}
+            }
+            let info = self.ensure_registered(token)?;
+            let caller = self.env().caller();
+            let contract = self.env().account_id();
+            (amount > 0).then_some(()).ok_or(Error::ZeroAmount)?;
+            let mut erc20_contract = self.get_erc20_ref(token);
+            let tip = tip.unwrap_or_default();
+            let total = amount.checked_add(tip).ok_or(Error::Overflow)?;
+            let allowance = erc20_contract.allowance(caller, contract);
+            (allowance >= total).then_some(()).ok_or(Error::Allowance {
+                allowance,
+                amount: total,
+            })?;
+            self.ensure_amount_within_limits(token, amount)?;
+            let volume = self.volume_after(token, Direction::Outbound, amount)?;
+            let fee = self.fees.get(token).unwrap_or_default().fee_for(amount);
+            (fee < amount)
+                .then_some(())
+                .ok_or(Error::FeeExceedsAmount { amount, fee })?;
+            let mint_burn = info.mode == TokenMode::MintBurn;
+            (!mint_burn || erc20_contract.minter() == contract)
+                .then_some(())
+                .ok_or(Error::BridgeIsNotMinter(token))?;
+            erc20_contract.transfer_from(caller, contract, total)?;
+            if mint_burn {
+                // Fees and tips stay on the bridge balance, only the bridged amount leaves the supply.
+                erc20_contract.burn(amount - fee)?;
+            }
+            if let Some(volume) = volume {
+                self.volumes.insert((token, Direction::Outbound), &volume);
+            }
+            let collected_fees = self.collected_fees.get(token).unwrap_or_default();
+            self.collected_fees.insert(token, &(collected_fees + fee));
+            self.counter += 1;
+            let global_id = self.global_id(
+                self.counter,
+                caller,
+                token,
+                external_destination_address,
+                amount,
+            );
+            self.global_ids.insert(global_id, &self.counter);
+            self.store_transfer(&Transfer {
+                id: self.counter,
+                global_id,
+                from: caller,
+                token,
+                to: external_destination_address,
+                gross_amount: amount,
+                fee,
+                amount: amount - fee,
+                tip,
+                status: TransferStatus::Queued,
+                history: ink_prelude::vec![StatusChange {
+                    status: TransferStatus::Queued,
+                    timestamp: self.env().block_timestamp(),
+                }],
+                settlement: None,
+                deadline,
+                lease: None,
+                failure: None,
+                retries: 0,
+                previous_destinations: Vec::new(),
+            });
+            self.index_by_sender(caller, self.counter);
+            self.index_by_destination(external_destination_address, self.counter);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Queued {
+                    id: self.counter,
+                    global_id,
+                    from: caller,
+                    token,
+                    to: external_destination_address,
+                    gross_amount: amount,
+                    fee,
+                    amount: amount - fee,
+                    tip,
+                    deadline,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(self.counter)
+        }
+
+        #[ink(message)]
+        pub fn refund(&mut self, transfer_id: u128) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Refund)?;
+            let mut transfer = self.find_transfer(transfer_id)?;
+            transfer.transition(TransferStatus::Refunded, self.env().block_timestamp())?;
+            let caller = self.env().caller();
+            if transfer.from != caller {
+                return Err(Error::RefundPermissionDenied);
+            }
+            self.return_to_sender(&transfer)?;
+            self.store_transfer(&transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Refund {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    to: caller,
+                    token: transfer.token,
+                    amount: transfer.amount,
+                    tip: transfer.tip,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Queues a failed transfer again, callable by its sender or an approved operator
+        /// at most `max_retries` times and not sooner than `cooldown` after the failure.
+        #[ink(message)]
+        pub fn try_again(&mut self, transfer_id: u128) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Deposit)?;
+            let mut transfer = self.find_transfer(transfer_id)?;
+            if let Some(code) = transfer.failure_code() {
+                code.ensure_retryable(transfer_id)?;
+            }
+            self.retry(&mut transfer)?;
+            self.store_transfer(&transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Queued {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    from: transfer.from,
+                    token: transfer.token,
+                    to: transfer.to,
+                    gross_amount: transfer.gross_amount,
+                    fee: transfer.fee,
+                    amount: transfer.amount,
+                    tip: transfer.tip,
+                    deadline: transfer.deadline,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Queues a failed transfer again to `new_destination`, callable by its sender only.
+        ///
+        /// It's the way to retry a transfer which failed because of its destination, the
+        /// retry policy applies the same way as to `try_again`.
+        #[ink(message)]
+        pub fn try_again_to(&mut self, transfer_id: u128, new_destination: [u8; 20]) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Deposit)?;
+            let mut transfer = self.find_transfer(transfer_id)?;
+            (transfer.from == self.env().caller())
+                .then_some(())
+                .ok_or(Error::DestinationChangePermissionDenied)?;
+            (transfer.to != new_destination)
+                .then_some(())
+                .ok_or(Error::SameDestination(transfer_id))?;
+            match transfer.failure_code() {
+                Some(code) if code != FailureCode::InvalidDestination => {
+                    code.ensure_retryable(transfer_id)?
+                }
+                _ => {}
+            }
+            self.retry(&mut transfer)?;
+            let old_destination = transfer.to;
+            // A destination used before is indexed already.
+            if !transfer.previous_destinations.contains(&new_destination) {
+                self.index_by_destination(new_destination, transfer_id);
+            }
+            transfer.previous_destinations.push(old_destination);
+            transfer.to = new_destination;
+            self.store_transfer(&transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                DestinationChanged {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    old_destination,
+                    new_destination,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Queued {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    from: transfer.from,
+                    token: transfer.token,
+                    to: transfer.to,
+                    gross_amount: transfer.gross_amount,
+                    fee: transfer.fee,
+                    amount: transfer.amount,
+                    tip: transfer.tip,
+                    deadline: transfer.deadline,
+                    timestamp: self.env().block_timestamp(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Fails a queued transfer, or an in-flight one whose lease has expired, once its
+        /// deadline has passed, so the sender can refund it right away. Anyone can call it.
+        #[ink(message)]
+        pub fn expire(&mut self, transfer_id: u128) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            let now = self.env().block_timestamp();
+            let mut transfer = self.find_transfer(transfer_id)?;
+            if let Some(lease) = transfer.active_lease(now) {
+                return Err(Error::LeaseHeld {
+                    id: transfer_id,
+                    holder: lease.holder,
+                    expires_at: lease.expires_at,
+                });
+            }
+            transfer.transition(TransferStatus::Failed, now)?;
+            match transfer.deadline {
+                Some(deadline) if now > deadline => {}
+                deadline => {
+                    return Err(Error::DeadlineNotReached {
+                        id: transfer_id,
+                        deadline,
+                    })
+                }
+            }
+            transfer.failure = Some(Failure {
+                code: FailureCode::Expired,
+                detail: Vec::new(),
+            });
+            self.clear_votes(transfer_id);
+            self.store_transfer(&transfer);
+            ink_lang::codegen::EmitEvent::<Bridge>::emit_event(
+                self.env(),
+                Expired {
+                    id: transfer.id,
+                    global_id: transfer.global_id,
+                    from: transfer.from,
+                    deadline: transfer.deadline.unwrap_or_default(),
+                    timestamp: now,
+                },
+            );
+            Ok(())
+        }
+
+        /// Cancels a transfer which is still queued `reclaim_timeout` after it was queued
+        /// and returns its amount to the sender.
+        ///
+        /// A claimed transfer may be delivered already, so it's never returned this way. When
+        /// its executor disappears another executor claims it once the lease expires and
+        /// reports the outcome, or anyone fails it with `expire` after its deadline.
+        #[ink(message)]
+        pub fn reclaim(&mut self, transfer_id: u128) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Refund)?;
+            let mut transfer = self.find_transfer(transfer_id)?;
+            let available_at = transfer
+                .status_changed_at()
+                .saturating_add(self.reclaim_timeout);
+            transfer.transition(TransferStatus::Cancelled, self.env().block_timestamp())?;
+            (transfer.from == self.env().caller())
+                .then_some(())
+                .ok_or(Error::ReclaimPermissionDenied)?;
+            (self.env().block_timestamp() >= available_at)
+                .then_some(())
+                .ok_or(Error::ReclaimTooEarly { available_at })?;
+            self.settle_cancelled(&transfer)
+        }
+
+        /// Cancels a transfer within `cancel_window` after it was queued, as long as
+        /// no executor has claimed it, and returns its amount to the sender.
+        #[ink(message)]
+        pub fn cancel(&mut self, transfer_id: u128) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Refund)?;
+            let mut transfer = self.find_transfer(transfer_id)?;
+            let closed_at = transfer
+                .status_changed_at()
+                .saturating_add(self.cancel_window);
+            transfer.transition(TransferStatus::Cancelled, self.env().block_timestamp())?;
+            (transfer.from == self.env().caller())
+                .then_some(())
+                .ok_or(Error::CancelPermissionDenied)?;
+            (self.env().block_timestamp() <= closed_at)
+                .then_some(())
+                .ok_or(Error::CancelWindowClosed { closed_at })?;
+            self.settle_cancelled(&transfer)
+        }
+
+        /// Marks a queued transfer as in flight for the caller during `lease_duration`,
+        /// executors have to claim a transfer before delivering it on Ethereum so the sender
+        /// can't cancel it anymore and no other executor delivers it.
+        ///
+        /// The lease ends at the transfer deadline at the latest. Once it expires any executor
+        /// can claim the transfer again.
+        #[ink(message)]
+        pub fn claim(&mut self, transfer_id: u128, lease_duration: Timestamp) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            let executor = ExecutorId::Account(self.env().caller());
+            self.ensure_executor(executor)?;
+            self.grant_lease(transfer_id, executor, lease_duration)
+        }
+
+        /// Digest the `Eth` executors sign with `eth_sign` to claim in `claim_signed`.
+        ///
+        /// It covers the current attempt of the transfer, so the signature is void once the
+        /// transfer is tried again.
+        #[ink(message)]
+        pub fn get_claim_digest(
+            &self,
+            transfer_id: u128,
+            lease_duration: Timestamp,
+            valid_until: Timestamp,
+        ) -> Result<[u8; 32]> {
+            let transfer = self.find_transfer(transfer_id)?;
+            Ok(self.claim_digest(&transfer, lease_duration, valid_until))
+        }
+
+        /// Same as `claim`, but for the `Eth` executor which signed `get_claim_digest`,
+        /// so anyone can submit it until `valid_until`.
+        #[ink(message)]
+        pub fn claim_signed(
+            &mut self,
+            transfer_id: u128,
+            lease_duration: Timestamp,
+            valid_until: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            (self.env().block_timestamp() <= valid_until)
+                .then_some(())
+                .ok_or(Error::SignatureExpired(valid_until))?;
+            let transfer = self.find_transfer(transfer_id)?;
+            let digest = self.claim_digest(&transfer, lease_duration, valid_until);
+            let executor = self.recover_signers(digest, &[signature])?[0];
+            self.grant_lease(transfer_id, executor, lease_duration)
+        }
+
+        #[ink(message)]
+        pub fn is_released(&self, source: EthSource, eth_transfer_id: u128) -> Result<bool> {
+            let inbound_id = self.inbound_id(source, eth_transfer_id);
+            Ok(self.released_transfers.get(inbound_id).is_some())
+        }
+
+        /// Id the release of Ethereum transfer `eth_transfer_id` of `source` is recorded under.
+        #[ink(message)]
+        pub fn get_inbound_id(&self, source: EthSource, eth_transfer_id: u128) -> Result<Hash> {
+            Ok(self.inbound_id(source, eth_transfer_id))
+        }
+
+        /// Votes for paying out an inbound Ethereum transfer from the locked token balance,
+        /// tokens in mint/burn mode are minted instead.
+        ///
+        /// The payout happens once `threshold` executors have voted for the same
+        /// recipient and amount. `source` has to be trusted with `set_eth_source`, every
+        /// Ethereum transfer id of a source is released at most once.
+        #[ink(message)]
+        pub fn release(
+            &mut self,
+            source: EthSource,
+            eth_transfer_id: u128,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Release)?;
+            let executor = ExecutorId::Account(self.env().caller());
+            self.ensure_executor(executor)?;
+            self.vote_release(source, eth_transfer_id, executor, (token, to, amount))?;
+            Ok(())
+        }
+
+        /// Digest the `Eth` executors sign with `eth_sign` to vote in `release_signed`.
+        #[ink(message)]
+        pub fn get_release_digest(
+            &self,
+            source: EthSource,
+            eth_transfer_id: u128,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<[u8; 32]> {
+            let inbound_id = self.inbound_id(source, eth_transfer_id);
+            Ok(self.digest(Self::RELEASE_DOMAIN, (inbound_id, token, to, amount)))
+        }
+
+        /// Same as `release`, but counts a vote for every executor which signed
+        /// `get_release_digest`, so anyone can submit it.
+        ///
+        /// Signers who have already voted for this payout are skipped.
+        #[ink(message)]
+        pub fn release_signed(
+            &mut self,
+            source: EthSource,
+            eth_transfer_id: u128,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+            signatures: Vec<[u8; 65]>,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Release)?;
+            let vote = (token, to, amount);
+            let inbound_id = self.inbound_id(source, eth_transfer_id);
+            let digest = self.digest(Self::RELEASE_DOMAIN, (inbound_id, token, to, amount));
+            let mut signers = self.recover_signers(digest, &signatures)?;
+            // Conflicts are checked upfront, so no vote is counted when any of them fails.
+            for signer in signers.iter() {
+                match self.release_vote_of(inbound_id, *signer) {
+                    Some(previous) if previous != vote => {
+                        return Err(Error::ConflictingVote(eth_transfer_id))
+                    }
+                    _ => {}
+                }
+            }
+            signers.retain(|signer| self.release_vote_of(inbound_id, *signer).is_none());
+            (!signers.is_empty())
+                .then_some(())
+                .ok_or(Error::AlreadyVoted(eth_transfer_id))?;
+            for signer in signers {
+                if self.vote_release(source, eth_transfer_id, signer, vote)? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+
+        /// Votes for the outcome of a queued transfer, successful outcomes carry the
+        /// Ethereum transaction hash and log index of the delivery, failed ones carry
+        /// the failure code and up to 256 bytes of detail.
+        ///
+        /// The transfer is finalized once `threshold` executors have voted for the
+        /// same outcome. An executor can't change its vote. The first vote on a claimed
+        /// transfer has to come from the holder of its lease.
+        #[ink(message)]
+        pub fn process_transfer(&mut self, transfer_id: u128, outcome: Outcome) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            let executor = ExecutorId::Account(self.env().caller());
+            self.ensure_executor(executor)?;
+            self.vote_transfer(transfer_id, executor, outcome)?;
+            Ok(())
+        }
+
+        /// Votes for the outcomes of several queued transfers, emitting the same events as
+        /// `process_transfer` for each of them.
+        ///
+        /// In atomic mode the whole call is reverted with the first error, otherwise every
+        /// outcome is applied independently and its result is returned in the same order.
+        #[ink(message)]
+        pub fn process_transfers(
+            &mut self,
+            outcomes: Vec<(u128, Outcome)>,
+            atomic: bool,
+        ) -> Result<Vec<Result<()>>> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            let executor = ExecutorId::Account(self.env().caller());
+            self.ensure_executor(executor)?;
+            let mut results = Vec::with_capacity(outcomes.len());
+            for (transfer_id, outcome) in outcomes {
+                let result = self
+                    .vote_transfer(transfer_id, executor, outcome)
+                    .map(|_| ());
+                match result {
+                    Err(error) if atomic => return Err(error),
+                    result => results.push(result),
+                }
+            }
+            Ok(results)
+        }
+
+        /// Digest the `Eth` executors sign with `eth_sign` to vote in `process_transfer_signed`.
+        ///
+        /// It covers the current attempt of the transfer, so the signatures are void once the
+        /// transfer is tried again.
+        #[ink(message)]
+        pub fn get_process_digest(&self, transfer_id: u128, outcome: Outcome) -> Result<[u8; 32]> {
+            let transfer = self.find_transfer(transfer_id)?;
+            Ok(self.process_digest(&transfer, &outcome))
+        }
+
+        /// Same as `process_transfer`, but counts a vote for every executor which signed
+        /// `get_process_digest`, so anyone can submit it.
+        ///
+        /// Signers who have already voted for this outcome are skipped.
+        #[ink(message)]
+        pub fn process_transfer_signed(
+            &mut self,
+            transfer_id: u128,
+            outcome: Outcome,
+            signatures: Vec<[u8; 65]>,
+        ) -> Result<()> {
+            self.ensure_migrated()?;
+            self.ensure_not_paused(Operation::Process)?;
+            let transfer = self.find_transfer(transfer_id)?;
+            let digest = self.process_digest(&transfer, &outcome);
+            let mut signers = self.recover_signers(digest, &signatures)?;
+            // Conflicts are checked upfront, so no vote is counted when any of them fails.
+            for signer in signers.iter() {
+                match self.vote_of(transfer_id, *signer) {
+                    Some(vote) if vote != outcome => {
+                        return Err(Error::ConflictingVote(transfer_id))
+                    }
+                    _ => {}
+                }
+            }
+            signers.retain(|signer| self.vote_of(transfer_id, *signer).is_none());
+            (!signers.is_empty())
+                .then_some(())
+                .ok_or(Error::AlreadyVoted(transfer_id))?;
+            // The lease holder has to vote first on a claimed transfer.
+            let holder = transfer
+                .active_lease(self.env().block_timestamp())
+                .map(|lease| lease.holder);
+            signers.sort_by_key(|signer| Some(*signer) != holder);
+            for signer in signers {
+                if self.vote_transfer(transfer_id, signer, outcome.clone())? {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use ink_lang as ink;
+
+        type Event = <Bridge as ::ink_lang::reflect::ContractEventBase>::Type;
+
+        const TOKEN: [u8; 32] = [0x10; 32];
+        const DESTINATION: [u8; 20] = [0x20; 20];
+
+        fn accounts() -> ink_env::test::DefaultAccounts<ink_env::DefaultEnvironment> {
+            ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            ink_env::test::set_caller::<ink_env::DefaultEnvironment>(account);
+        }
+
+        fn now() -> Timestamp {
+            ink_env::block_timestamp::<ink_env::DefaultEnvironment>()
+        }
+
+        fn advance_blocks(count: u32) {
+            for _ in 0..count {
+                ink_env::test::advance_block::<ink_env::DefaultEnvironment>();
+            }
+        }
+
+        /// Alice, Bob and Charlie are the executors, two of them finalize a transfer.
+        fn new_bridge() -> Bridge {
+            let accounts = accounts();
+            set_caller(accounts.alice);
+            Bridge::new_with_executors(
+                42,
+                ink_prelude::vec![
+                    ExecutorId::Account(accounts.alice),
+                    ExecutorId::Account(accounts.bob),
+                    ExecutorId::Account(accounts.charlie),
+                ],
+                2,
+            )
+        }
+
+        /// Queues a transfer from Django without tip, so it's processed without token calls.
+        fn queue_transfer(bridge: &mut Bridge, deadline: Option<Timestamp>) -> u128 {
+            let from = accounts().django;
+            bridge.counter += 1;
+            bridge.store_transfer(&Transfer {
+                id: bridge.counter,
+                global_id: Hash::from([bridge.counter as u8; 32]),
+                from,
+                token: AccountId::from(TOKEN),
+                to: DESTINATION,
+                gross_amount: 100,
+                fee: 0,
+                amount: 100,
+                tip: 0,
+                status: TransferStatus::Queued,
+                history: ink_prelude::vec![StatusChange {
+                    status: TransferStatus::Queued,
+                    timestamp: now(),
+                }],
+                settlement: None,
+                deadline,
+                lease: None,
+                failure: None,
+                retries: 0,
+                previous_destinations: Vec::new(),
+            });
+            bridge
+                .global_ids
+                .insert(Hash::from([bridge.counter as u8; 32]), &bridge.counter);
+            bridge.index_by_sender(from, bridge.counter);
+            bridge.index_by_destination(DESTINATION, bridge.counter);
+            bridge.counter
+        }
+
+        fn transfer(bridge: &Bridge, transfer_id: u128) -> Transfer {
+            bridge.find_transfer(transfer_id).unwrap()
+        }
+
+        fn failed(code: FailureCode) -> Outcome {
+            Outcome::Failed(Failure {
+                code,
+                detail: Vec::new(),
+            })
+        }
+
+        fn succeeded() -> Outcome {
+            Outcome::Succeeded(Settlement {
+                tx_hash: [0x30; 32],
+                log_index: 0,
+            })
+        }
+
+        fn vote(
+            bridge: &mut Bridge,
+            executor: AccountId,
+            transfer_id: u128,
+            outcome: Outcome,
+        ) -> Result<()> {
+            set_caller(executor);
+            bridge.process_transfer(transfer_id, outcome)
+        }
+
+        /// Ethereum address of the executor key derived from `seed`.
+        fn eth_executor(seed: u8) -> ExecutorId {
+            let secret = secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap();
+            let public_key = secp256k1::PublicKey::from_secret_key_global(&secret).serialize();
+            let mut address = [0; 20];
+            ink_env::ecdsa_to_eth_address(&public_key, &mut address).unwrap();
+            ExecutorId::Eth(address)
+        }
+
+        /// Signs `digest` with the executor key derived from `seed` the way `eth_sign` does.
+        fn eth_sign(seed: u8, digest: [u8; 32]) -> [u8; 65] {
+            let secret = secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap();
+            let mut message = b"\x19Ethereum Signed Message:\n32".to_vec();
+            message.extend_from_slice(&digest);
+            let mut message_hash = [0; 32];
+            ink_env::hash_bytes::<Keccak256>(&message, &mut message_hash);
+            let (recovery_id, compact) = secp256k1::SECP256K1
+                .sign_ecdsa_recoverable(
+                    &secp256k1::Message::from_slice(&message_hash).unwrap(),
+                    &secret,
+                )
+                .serialize_compact();
+            let mut signature = [0; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8 + 27;
+            signature
+        }
+
+        fn fail(bridge: &mut Bridge, transfer_id: u128, code: FailureCode) {
+            let accounts = accounts();
+            vote(bridge, accounts.alice, transfer_id, failed(code)).unwrap();
+            vote(bridge, accounts.bob, transfer_id, failed(code)).unwrap();
+        }
+
+        #[ink::test]
+        fn pending_migration_blocks_state_changes() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            // Storage as left behind by the previous code after `set_code`.
+            bridge.storage_version = Bridge::STORAGE_VERSION - 1;
+            let pending = Err(Error::MigrationPending(Bridge::STORAGE_VERSION - 1));
+
+            assert_eq!(
+                bridge.register_token(
+                    AccountId::from(TOKEN),
+                    [0x11; 20],
+                    18,
+                    TokenMode::LockRelease
+                ),
+                pending
+            );
+            assert_eq!(
+                bridge.set_fee_schedule(AccountId::from(TOKEN), 1, 0),
+                pending
+            );
+            assert_eq!(bridge.set_operator(accounts.bob, true), pending);
+            assert_eq!(bridge.claim(transfer_id, 60), pending);
+
+            set_caller(accounts.bob);
+            assert_eq!(bridge.migrate(), Err(Error::AdminPermissionDenied));
+            set_caller(accounts.alice);
+            assert_eq!(bridge.migrate(), Ok(()));
+            assert_eq!(
+                bridge.migrate(),
+                Err(Error::AlreadyMigrated(Bridge::STORAGE_VERSION))
+            );
+            assert_eq!(bridge.claim(transfer_id, 60), Ok(()));
+        }
+
+        #[ink::test]
+        fn executor_set_and_threshold_are_configurable() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let outcome = failed(FailureCode::Other);
+            vote(&mut bridge, accounts.alice, transfer_id, outcome.clone()).unwrap();
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.set_threshold(4),
+                Err(Error::InvalidThreshold {
+                    threshold: 4,
+                    executors: 3,
+                })
+            );
+            assert_eq!(
+                bridge.set_threshold(0),
+                Err(Error::InvalidThreshold {
+                    threshold: 0,
+                    executors: 3,
+                })
+            );
+            let eve = ExecutorId::Account(accounts.eve);
+            assert_eq!(bridge.add_executor(eve), Ok(()));
+            assert_eq!(bridge.add_executor(eve), Err(Error::DuplicateExecutor(eve)));
+            assert_eq!(bridge.set_threshold(4), Ok(()));
+            assert_eq!(
+                bridge.remove_executor(eve),
+                Err(Error::InvalidThreshold {
+                    threshold: 4,
+                    executors: 3,
+                })
+            );
+            assert_eq!(bridge.set_threshold(1), Ok(()));
+            assert_eq!(bridge.remove_executor(eve), Ok(()));
+            assert_eq!(
+                bridge.remove_executor(eve),
+                Err(Error::UnknownExecutor(eve))
+            );
+            assert_eq!(bridge.get_executors().unwrap().len(), 3);
+
+            // Changing the set discards the pending vote, so Alice votes again.
+            assert_eq!(bridge.vote_count(transfer_id, &outcome), 0);
+            vote(&mut bridge, accounts.alice, transfer_id, outcome).unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Failed
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(bridge.set_threshold(2), Err(Error::AdminPermissionDenied));
+        }
+
+        #[ink::test]
+        fn signed_votes_are_bound_to_the_attempt() {
+            let accounts = accounts();
+            let mut bridge = Bridge::new_with_executors(
+                42,
+                ink_prelude::vec![eth_executor(1), eth_executor(2)],
+                2,
+            );
+            bridge
+                .set_retry_policy(RetryPolicy {
+                    max_retries: 3,
+                    cooldown: 0,
+                })
+                .unwrap();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let outcome = failed(FailureCode::InsufficientLiquidity);
+            let digest = bridge
+                .get_process_digest(transfer_id, outcome.clone())
+                .unwrap();
+            let signatures = ink_prelude::vec![eth_sign(1, digest), eth_sign(2, digest)];
+
+            set_caller(accounts.eve);
+            assert_eq!(
+                bridge.process_transfer_signed(
+                    transfer_id,
+                    outcome.clone(),
+                    ink_prelude::vec![eth_sign(1, digest), eth_sign(1, digest)]
+                ),
+                Err(Error::DuplicateSignature(match eth_executor(1) {
+                    ExecutorId::Eth(address) => address,
+                    _ => unreachable!(),
+                }))
+            );
+            assert_eq!(
+                bridge.process_transfer_signed(
+                    transfer_id,
+                    outcome.clone(),
+                    ink_prelude::vec![[0; 65]]
+                ),
+                Err(Error::InvalidSignature(0))
+            );
+            assert!(matches!(
+                bridge.process_transfer_signed(
+                    transfer_id,
+                    outcome.clone(),
+                    ink_prelude::vec![eth_sign(3, digest)]
+                ),
+                Err(Error::UnknownExecutor(_))
+            ));
+            bridge
+                .process_transfer_signed(transfer_id, outcome.clone(), signatures.clone())
+                .unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Failed
+            );
+
+            set_caller(accounts.django);
+            bridge.try_again(transfer_id).unwrap();
+            // The signatures of the previous attempt don't recover to the executors anymore.
+            set_caller(accounts.eve);
+            assert!(matches!(
+                bridge.process_transfer_signed(transfer_id, outcome.clone(), signatures),
+                Err(Error::UnknownExecutor(_))
+            ));
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Queued
+            );
+            assert_ne!(bridge.get_process_digest(transfer_id, outcome), Ok(digest));
+        }
+
+        #[ink::test]
+        fn signed_claim_leases_to_eth_executor() {
+            let accounts = accounts();
+            let mut bridge = Bridge::new_with_executors(
+                42,
+                ink_prelude::vec![eth_executor(1), eth_executor(2)],
+                2,
+            );
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let valid_until = now() + 6;
+            let claim_digest = bridge
+                .get_claim_digest(transfer_id, 60, valid_until)
+                .unwrap();
+
+            set_caller(accounts.eve);
+            bridge
+                .claim_signed(transfer_id, 60, valid_until, eth_sign(2, claim_digest))
+                .unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).lease,
+                Some(Lease {
+                    holder: eth_executor(2),
+                    expires_at: now() + 60,
+                })
+            );
+            advance_blocks(2);
+            assert_eq!(
+                bridge.claim_signed(transfer_id, 60, valid_until, eth_sign(1, claim_digest)),
+                Err(Error::SignatureExpired(valid_until))
+            );
+
+            // The lease holder's vote is counted first whatever the order of signatures.
+            let digest = bridge.get_process_digest(transfer_id, succeeded()).unwrap();
+            bridge
+                .process_transfer_signed(
+                    transfer_id,
+                    succeeded(),
+                    ink_prelude::vec![eth_sign(1, digest), eth_sign(2, digest)],
+                )
+                .unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Succeeded
+            );
+        }
+
+        #[ink::test]
+        fn tip_is_owed_to_eth_lease_holder() {
+            let accounts = accounts();
+            let token = AccountId::from(TOKEN);
+            let mut bridge = Bridge::new_with_executors(
+                42,
+                ink_prelude::vec![eth_executor(1), eth_executor(2)],
+                2,
+            );
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let mut tipped = transfer(&bridge, transfer_id);
+            tipped.tip = 7;
+            bridge.transfers.insert(transfer_id, &tipped);
+            bridge.locked_tips.insert(token, &7);
+            assert_eq!(bridge.liabilities(token), 107);
+
+            let valid_until = now() + 60;
+            let claim_digest = bridge
+                .get_claim_digest(transfer_id, 60, valid_until)
+                .unwrap();
+            set_caller(accounts.eve);
+            bridge
+                .claim_signed(transfer_id, 60, valid_until, eth_sign(2, claim_digest))
+                .unwrap();
+            // Eve submits the finalizing signatures, but executor 2 delivered the transfer.
+            let digest = bridge.get_process_digest(transfer_id, succeeded()).unwrap();
+            bridge
+                .process_transfer_signed(
+                    transfer_id,
+                    succeeded(),
+                    ink_prelude::vec![eth_sign(1, digest), eth_sign(2, digest)],
+                )
+                .unwrap();
+
+            let address = |executor| match executor {
+                ExecutorId::Eth(address) => address,
+                _ => unreachable!(),
+            };
+            assert_eq!(bridge.get_owed_tips(address(eth_executor(2)), token), Ok(7));
+            assert_eq!(bridge.get_owed_tips(address(eth_executor(1)), token), Ok(0));
+            assert_eq!(bridge.locked_tips.get(token), Some(0));
+            // The delivered amount isn't owed anymore, the tip still is.
+            assert_eq!(bridge.liabilities(token), 7);
+
+            let tips_digest = bridge
+                .get_tips_digest(address(eth_executor(2)), token, accounts.eve)
+                .unwrap();
+            assert_eq!(
+                bridge.withdraw_tips_signed(
+                    address(eth_executor(2)),
+                    token,
+                    accounts.eve,
+                    eth_sign(1, tips_digest)
+                ),
+                Err(Error::InvalidSignature(0))
+            );
+        }
+
+        #[ink::test]
+        fn fee_for_adds_flat_and_proportional_parts() {
+            let fees = FeeSchedule {
+                flat: 5,
+                basis_points: 30,
+            };
+            assert_eq!(fees.fee_for(10_000), 35);
+            assert_eq!(fees.fee_for(333), 5);
+            assert_eq!(FeeSchedule::default().fee_for(1_000), 0);
+            let everything = FeeSchedule {
+                flat: 0,
+                basis_points: FeeSchedule::MAX_BASIS_POINTS,
+            };
+            assert_eq!(everything.fee_for(Balance::MAX), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn volume_window_advance_rolls_over() {
+            let volume = VolumeWindow {
+                started_at: 100,
+                used: 50,
+                previous_used: 20,
+            };
+            assert_eq!(volume.advance(199, 100), volume);
+            assert_eq!(
+                volume.advance(250, 100),
+                VolumeWindow {
+                    started_at: 200,
+                    used: 0,
+                    previous_used: 50,
+                }
+            );
+            assert_eq!(
+                volume.advance(450, 100),
+                VolumeWindow {
+                    started_at: 400,
+                    used: 0,
+                    previous_used: 0,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn volume_window_usage_weights_previous_window() {
+            let volume = VolumeWindow {
+                started_at: 100,
+                used: 10,
+                previous_used: 50,
+            };
+            assert_eq!(volume.usage(100, 100), 60);
+            assert_eq!(volume.usage(150, 100), 35);
+            assert_eq!(volume.usage(199, 100), 10);
+        }
+
+        #[ink::test]
+        fn status_transitions_are_checked() {
+            use TransferStatus::*;
+            assert!(Queued.can_become(InFlight));
+            assert!(Failed.can_become(Queued));
+            assert!(!Queued.can_become(Succeeded));
+            assert!(!Succeeded.can_become(Failed));
+            assert!(!Refunded.can_become(Queued));
+            assert!(!Cancelled.can_become(Queued));
+            assert!(!InFlight.can_become(Cancelled));
+
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let mut transfer = transfer(&bridge, transfer_id);
+            assert_eq!(
+                transfer.transition(Succeeded, now()),
+                Err(Error::InvalidTransition {
+                    id: transfer_id,
+                    from: Queued,
+                    to: Succeeded,
+                })
+            );
+            transfer.transition(InFlight, now()).unwrap();
+            assert_eq!(transfer.status, InFlight);
+            assert_eq!(transfer.history.len(), 2);
+        }
+
+        #[ink::test]
+        fn votes_finalize_transfer_at_threshold() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let outcome = failed(FailureCode::InsufficientLiquidity);
+
+            vote(&mut bridge, accounts.alice, transfer_id, outcome.clone()).unwrap();
+            assert_eq!(
+                vote(&mut bridge, accounts.alice, transfer_id, outcome.clone()),
+                Err(Error::AlreadyVoted(transfer_id))
+            );
+            assert_eq!(
+                vote(
+                    &mut bridge,
+                    accounts.alice,
+                    transfer_id,
+                    failed(FailureCode::Other)
+                ),
+                Err(Error::ConflictingVote(transfer_id))
+            );
+            assert_eq!(
+                vote(&mut bridge, accounts.eve, transfer_id, outcome.clone()),
+                Err(Error::ExecutorPermissionDenied)
+            );
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Queued
+            );
+
+            vote(
+                &mut bridge,
+                accounts.bob,
+                transfer_id,
+                failed(FailureCode::Other),
+            )
+            .unwrap();
+            let conflicts = ink_env::test::recorded_events()
+                .filter(|event| {
+                    matches!(
+                        <Event as scale::Decode>::decode(&mut &event.data[..]),
+                        Ok(Event::ConflictingVotes(ConflictingVotes {
+                            conflicting_votes: 1,
+                            ..
+                        }))
+                    )
+                })
+                .count();
+            assert_eq!(conflicts, 1);
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Queued
+            );
+
+            vote(&mut bridge, accounts.charlie, transfer_id, outcome.clone()).unwrap();
+            let transfer = transfer(&bridge, transfer_id);
+            assert_eq!(transfer.status, TransferStatus::Failed);
+            assert_eq!(Outcome::Failed(transfer.failure.unwrap()), outcome);
+            assert_eq!(
+                bridge.vote_of(transfer_id, ExecutorId::Account(accounts.alice)),
+                None
+            );
+            assert_eq!(bridge.vote_count(transfer_id, &outcome), 0);
+        }
+
+        #[ink::test]
+        fn lease_holder_votes_first_on_claimed_transfer() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 60).unwrap();
+
+            assert_eq!(
+                vote(&mut bridge, accounts.bob, transfer_id, succeeded()),
+                Err(Error::LeasePermissionDenied(transfer_id))
+            );
+            vote(&mut bridge, accounts.alice, transfer_id, succeeded()).unwrap();
+            vote(&mut bridge, accounts.bob, transfer_id, succeeded()).unwrap();
+            let transfer = transfer(&bridge, transfer_id);
+            assert_eq!(transfer.status, TransferStatus::Succeeded);
+            assert_eq!(
+                transfer.settlement,
+                Some(Settlement {
+                    tx_hash: [0x30; 32],
+                    log_index: 0,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn expired_lease_is_taken_over() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 10).unwrap();
+            let expires_at = now() + 10;
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.claim(transfer_id, 10),
+                Err(Error::LeaseHeld {
+                    id: transfer_id,
+                    holder: ExecutorId::Account(accounts.alice),
+                    expires_at,
+                })
+            );
+            set_caller(accounts.eve);
+            assert_eq!(
+                bridge.claim(transfer_id, 10),
+                Err(Error::ExecutorPermissionDenied)
+            );
+
+            advance_blocks(2);
+            set_caller(accounts.bob);
+            bridge.claim(transfer_id, 10).unwrap();
+            let transfer = transfer(&bridge, transfer_id);
+            assert_eq!(transfer.status, TransferStatus::InFlight);
+            assert_eq!(transfer.history.len(), 2);
+            assert_eq!(
+                transfer.lease,
+                Some(Lease {
+                    holder: ExecutorId::Account(accounts.bob),
+                    expires_at: now() + 10,
+                })
+            );
+            assert_eq!(
+                vote(&mut bridge, accounts.alice, transfer_id, succeeded()),
+                Err(Error::LeasePermissionDenied(transfer_id))
+            );
+        }
+
+        #[ink::test]
+        fn lease_ends_at_deadline_and_blocks_expiry() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let deadline = now() + 10;
+            let transfer_id = queue_transfer(&mut bridge, Some(deadline));
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 1_000).unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).lease,
+                Some(Lease {
+                    holder: ExecutorId::Account(accounts.alice),
+                    expires_at: deadline,
+                })
+            );
+            assert_eq!(
+                bridge.expire(transfer_id),
+                Err(Error::LeaseHeld {
+                    id: transfer_id,
+                    holder: ExecutorId::Account(accounts.alice),
+                    expires_at: deadline,
+                })
+            );
+
+            advance_blocks(2);
+            assert_eq!(
+                vote(&mut bridge, accounts.alice, transfer_id, succeeded()),
+                Err(Error::LeasePermissionDenied(transfer_id))
+            );
+            bridge.expire(transfer_id).unwrap();
+            let transfer = transfer(&bridge, transfer_id);
+            assert_eq!(transfer.status, TransferStatus::Failed);
+            assert_eq!(transfer.failure.unwrap().code, FailureCode::Expired);
+        }
+
+        #[ink::test]
+        fn claimed_transfer_cannot_be_cancelled() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 60).unwrap();
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.cancel(transfer_id),
+                Err(Error::InvalidTransition {
+                    id: transfer_id,
+                    from: TransferStatus::InFlight,
+                    to: TransferStatus::Cancelled,
+                })
+            );
+            set_caller(accounts.alice);
+            bridge.set_reclaim_timeout(0).unwrap();
+            advance_blocks(20);
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.reclaim(transfer_id),
+                Err(Error::InvalidTransition {
+                    id: transfer_id,
+                    from: TransferStatus::InFlight,
+                    to: TransferStatus::Cancelled,
+                })
+            );
+
+            // Another executor takes over the abandoned transfer and reports its outcome.
+            set_caller(accounts.bob);
+            bridge.claim(transfer_id, 60).unwrap();
+            let outcome = failed(FailureCode::InsufficientLiquidity);
+            vote(&mut bridge, accounts.bob, transfer_id, outcome.clone()).unwrap();
+            vote(&mut bridge, accounts.alice, transfer_id, outcome).unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Failed
+            );
+        }
+
+        #[ink::test]
+        fn retries_are_limited() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            bridge
+                .set_retry_policy(RetryPolicy {
+                    max_retries: 1,
+                    cooldown: 10,
+                })
+                .unwrap();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            fail(&mut bridge, transfer_id, FailureCode::InsufficientLiquidity);
+
+            set_caller(accounts.eve);
+            assert_eq!(
+                bridge.try_again(transfer_id),
+                Err(Error::RetryPermissionDenied)
+            );
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.try_again(transfer_id),
+                Err(Error::RetryTooEarly {
+                    available_at: now() + 10,
+                })
+            );
+            bridge.set_operator(accounts.eve, true).unwrap();
+
+            advance_blocks(2);
+            set_caller(accounts.eve);
+            bridge.try_again(transfer_id).unwrap();
+            let retried = transfer(&bridge, transfer_id);
+            assert_eq!(retried.status, TransferStatus::Queued);
+            assert_eq!(retried.retries, 1);
+
+            fail(&mut bridge, transfer_id, FailureCode::InsufficientLiquidity);
+            advance_blocks(2);
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.try_again(transfer_id),
+                Err(Error::TooManyRetries {
+                    id: transfer_id,
+                    max_retries: 1,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn failed_destination_requires_new_one() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            bridge
+                .set_retry_policy(RetryPolicy {
+                    max_retries: 2,
+                    cooldown: 0,
+                })
+                .unwrap();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            fail(&mut bridge, transfer_id, FailureCode::InvalidDestination);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.try_again(transfer_id),
+                Err(Error::DestinationChangeRequired(transfer_id))
+            );
+            bridge.try_again_to(transfer_id, [0x21; 20]).unwrap();
+            fail(&mut bridge, transfer_id, FailureCode::InvalidDestination);
+            set_caller(accounts.django);
+            bridge.try_again_to(transfer_id, DESTINATION).unwrap();
+
+            let transfer = transfer(&bridge, transfer_id);
+            assert_eq!(transfer.to, DESTINATION);
+            assert_eq!(
+                transfer.previous_destinations,
+                ink_prelude::vec![DESTINATION, [0x21; 20]]
+            );
+            assert_eq!(bridge.get_destination_transfer_count(DESTINATION), Ok(1));
+            assert_eq!(bridge.get_destination_transfer_count([0x21; 20]), Ok(1));
+        }
+
+        #[ink::test]
+        fn delivered_transfer_cannot_be_tried_again() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            bridge
+                .set_retry_policy(RetryPolicy {
+                    max_retries: 1,
+                    cooldown: 0,
+                })
+                .unwrap();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            fail(&mut bridge, transfer_id, FailureCode::InvalidDestination);
+            set_caller(accounts.django);
+            bridge.try_again_to(transfer_id, [0x21; 20]).unwrap();
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 60).unwrap();
+            vote(&mut bridge, accounts.alice, transfer_id, succeeded()).unwrap();
+            vote(&mut bridge, accounts.bob, transfer_id, succeeded()).unwrap();
+
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.try_again(transfer_id),
+                Err(Error::InvalidTransition {
+                    id: transfer_id,
+                    from: TransferStatus::Succeeded,
+                    to: TransferStatus::Queued,
+                })
+            );
+        }
+        #[ink::test]
+        fn releases_are_keyed_by_source() {
+            let accounts = accounts();
+            let token = AccountId::from(TOKEN);
+            let mut bridge = new_bridge();
+            bridge
+                .register_token(token, [0x11; 20], 18, TokenMode::LockRelease)
+                .unwrap();
+            let source = EthSource {
+                chain_id: 1,
+                bridge: [0x40; 20],
+            };
+            let other_chain = EthSource {
+                chain_id: 5,
+                ..source
+            };
+            let other_bridge = EthSource {
+                bridge: [0x41; 20],
+                ..source
+            };
+
+            assert_eq!(
+                bridge.release(source, 1, token, accounts.django, 100),
+                Err(Error::UnknownSource(source))
+            );
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.set_eth_source(source, true),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge.set_eth_source(source, true).unwrap();
+            bridge.set_eth_source(other_chain, true).unwrap();
+            assert_eq!(bridge.is_trusted_source(other_bridge), Ok(false));
+
+            let inbound_id = bridge.get_inbound_id(source, 1).unwrap();
+            assert_ne!(Ok(inbound_id), bridge.get_inbound_id(other_chain, 1));
+            assert_ne!(Ok(inbound_id), bridge.get_inbound_id(other_bridge, 1));
+            assert_ne!(
+                bridge.get_release_digest(source, 1, token, accounts.django, 100),
+                bridge.get_release_digest(other_chain, 1, token, accounts.django, 100)
+            );
+
+            // Payouts need token calls, so the first release is recorded directly.
+            bridge.released_transfers.insert(inbound_id, &());
+            assert_eq!(bridge.is_released(source, 1), Ok(true));
+            assert_eq!(bridge.is_released(other_chain, 1), Ok(false));
+            assert_eq!(
+                bridge.release(source, 1, token, accounts.django, 100),
+                Err(Error::AlreadyReleased(inbound_id))
+            );
+            bridge
+                .release(other_chain, 1, token, accounts.django, 100)
+                .unwrap();
+            assert_eq!(
+                bridge.release(other_chain, 1, token, accounts.django, 100),
+                Err(Error::AlreadyVoted(1))
+            );
+
+            bridge.set_eth_source(other_chain, false).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.release(other_chain, 1, token, accounts.django, 100),
+                Err(Error::UnknownSource(other_chain))
+            );
+        }
+
+        #[ink::test]
+        fn release_needs_threshold_matching_votes() {
+            let accounts = accounts();
+            let token = AccountId::from(TOKEN);
+            let mut bridge = new_bridge();
+            bridge
+                .register_token(token, [0x11; 20], 18, TokenMode::LockRelease)
+                .unwrap();
+            let source = EthSource {
+                chain_id: 1,
+                bridge: [0x40; 20],
+            };
+            bridge.set_eth_source(source, true).unwrap();
+            let inbound_id = bridge.get_inbound_id(source, 7).unwrap();
+
+            bridge
+                .release(source, 7, token, accounts.django, 100)
+                .unwrap();
+            assert_eq!(
+                bridge.release(source, 7, token, accounts.django, 100),
+                Err(Error::AlreadyVoted(7))
+            );
+            // Votes for another payout of the same transfer are counted separately.
+            set_caller(accounts.bob);
+            bridge.release(source, 7, token, accounts.eve, 100).unwrap();
+            assert_eq!(
+                bridge.release(source, 7, token, accounts.django, 100),
+                Err(Error::ConflictingVote(7))
+            );
+            assert_eq!(
+                bridge.release_vote_count(inbound_id, (token, accounts.django, 100)),
+                1
+            );
+            assert_eq!(
+                bridge.release_vote_count(inbound_id, (token, accounts.eve, 100)),
+                1
+            );
+            assert_eq!(bridge.is_released(source, 7), Ok(false));
+
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.release(source, 7, token, accounts.django, 100),
+                Err(Error::ExecutorPermissionDenied)
+            );
+            set_caller(accounts.charlie);
+            assert_eq!(
+                bridge.release(source, 7, token, accounts.django, 0),
+                Err(Error::ZeroAmount)
+            );
+            assert_eq!(
+                bridge.release(source, 7, AccountId::from([0x12; 32]), accounts.django, 100),
+                Err(Error::UnsupportedToken(AccountId::from([0x12; 32])))
+            );
+        }
+
+        #[ink::test]
+        fn executor_rotation_and_ownership_transfer() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let outcome = failed(FailureCode::Other);
+            vote(&mut bridge, accounts.alice, transfer_id, outcome.clone()).unwrap();
+
+            let alice = ExecutorId::Account(accounts.alice);
+            let bob = ExecutorId::Account(accounts.bob);
+            let eve = ExecutorId::Account(accounts.eve);
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.set_executor(bob, eve),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.set_executor(alice, bob),
+                Err(Error::DuplicateExecutor(bob))
+            );
+            assert_eq!(
+                bridge.set_executor(ExecutorId::Account(accounts.django), eve),
+                Err(Error::UnknownExecutor(ExecutorId::Account(accounts.django)))
+            );
+            bridge.set_executor(bob, eve).unwrap();
+            assert_eq!(
+                bridge.get_executors(),
+                Ok(ink_prelude::vec![
+                    alice,
+                    eve,
+                    ExecutorId::Account(accounts.charlie)
+                ])
+            );
+            // The rotation discards the pending vote and Bob can't vote anymore.
+            assert_eq!(bridge.vote_count(transfer_id, &outcome), 0);
+            assert_eq!(
+                vote(&mut bridge, accounts.bob, transfer_id, outcome.clone()),
+                Err(Error::ExecutorPermissionDenied)
+            );
+            vote(&mut bridge, accounts.eve, transfer_id, outcome.clone()).unwrap();
+            vote(&mut bridge, accounts.alice, transfer_id, outcome).unwrap();
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Failed
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.transfer_ownership(accounts.bob),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge.transfer_ownership(accounts.bob).unwrap();
+            assert_eq!(bridge.get_admin(), Ok(accounts.alice));
+            assert_eq!(bridge.get_pending_admin(), Ok(Some(accounts.bob)));
+            set_caller(accounts.charlie);
+            assert_eq!(
+                bridge.accept_ownership(),
+                Err(Error::PendingAdminPermissionDenied)
+            );
+            set_caller(accounts.bob);
+            bridge.accept_ownership().unwrap();
+            assert_eq!(bridge.get_admin(), Ok(accounts.bob));
+            assert_eq!(bridge.get_pending_admin(), Ok(None));
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.set_executor(eve, bob),
+                Err(Error::AdminPermissionDenied)
+            );
+        }
+
+        #[ink::test]
+        fn paused_operations_are_blocked_until_unpaused() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            assert_eq!(bridge.get_pauser(), Ok(accounts.alice));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.set_pauser(accounts.eve),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge.set_pauser(accounts.eve).unwrap();
+            assert_eq!(
+                bridge.pause(ink_prelude::vec![Operation::Process]),
+                Err(Error::PauserPermissionDenied)
+            );
+            set_caller(accounts.eve);
+            bridge
+                .pause(ink_prelude::vec![Operation::Process, Operation::Refund])
+                .unwrap();
+            assert_eq!(bridge.is_paused(Operation::Process), Ok(true));
+            assert_eq!(bridge.is_paused(Operation::Deposit), Ok(false));
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.claim(transfer_id, 60),
+                Err(Error::Paused(Operation::Process))
+            );
+            assert_eq!(
+                vote(&mut bridge, accounts.alice, transfer_id, succeeded()),
+                Err(Error::Paused(Operation::Process))
+            );
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.cancel(transfer_id),
+                Err(Error::Paused(Operation::Refund))
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.unpause(ink_prelude::vec![Operation::Process]),
+                Err(Error::PauserPermissionDenied)
+            );
+            set_caller(accounts.eve);
+            bridge
+                .unpause(ink_prelude::vec![Operation::Process])
+                .unwrap();
+            assert_eq!(bridge.is_paused(Operation::Refund), Ok(true));
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 60).unwrap();
+        }
+
+        #[ink::test]
+        fn token_registry_tracks_many_tokens() {
+            let accounts = accounts();
+            let token = AccountId::from(TOKEN);
+            let other = AccountId::from([0x12; 32]);
+            let mut bridge = new_bridge();
+            assert_eq!(bridge.get_token(token), Ok(None));
+            assert_eq!(
+                bridge.unregister_token(token),
+                Err(Error::UnsupportedToken(token))
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.register_token(token, [0x11; 20], 18, TokenMode::LockRelease),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge
+                .register_token(token, [0x11; 20], 18, TokenMode::LockRelease)
+                .unwrap();
+            bridge
+                .register_token(other, [0x13; 20], 6, TokenMode::MintBurn)
+                .unwrap();
+            assert_eq!(
+                bridge.get_token(other),
+                Ok(Some(TokenInfo {
+                    eth_address: [0x13; 20],
+                    decimals: 6,
+                    mode: TokenMode::MintBurn,
+                    enabled: true,
+                }))
+            );
+
+            // The mode is locked while transfers of the token are outstanding.
+            bridge.queued_amounts.insert(token, &100);
+            assert_eq!(
+                bridge.register_token(token, [0x11; 20], 18, TokenMode::MintBurn),
+                Err(Error::TokenModeLocked(token))
+            );
+            bridge
+                .register_token(token, [0x14; 20], 18, TokenMode::LockRelease)
+                .unwrap();
+
+            bridge.unregister_token(token).unwrap();
+            assert_eq!(
+                bridge.get_token(token),
+                Ok(Some(TokenInfo {
+                    eth_address: [0x14; 20],
+                    decimals: 18,
+                    mode: TokenMode::LockRelease,
+                    enabled: false,
+                }))
+            );
+            assert_eq!(
+                bridge.ensure_registered(token),
+                Err(Error::UnsupportedToken(token))
+            );
+            assert!(bridge.ensure_registered(other).is_ok());
+        }
+
+        #[ink::test]
+        fn queued_transfer_is_reclaimed_by_sender_after_timeout() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.set_reclaim_timeout(60),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge.set_reclaim_timeout(60).unwrap();
+            assert_eq!(bridge.get_reclaim_timeout(), Ok(60));
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let available_at = now() + 60;
+
+            assert_eq!(
+                bridge.reclaim(transfer_id),
+                Err(Error::ReclaimPermissionDenied)
+            );
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.reclaim(transfer_id),
+                Err(Error::ReclaimTooEarly { available_at })
+            );
+            advance_blocks(9);
+            assert_eq!(
+                bridge.reclaim(transfer_id),
+                Err(Error::ReclaimTooEarly { available_at })
+            );
+            assert_eq!(
+                bridge.reclaim(transfer_id + 1),
+                Err(Error::NotFound(transfer_id + 1))
+            );
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Queued
+            );
+        }
+
+        #[ink::test]
+        fn settlement_is_recorded_on_success() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let global_id = transfer(&bridge, transfer_id).global_id;
+            assert_eq!(
+                bridge.get_settlement(TransferKey::Nonce(transfer_id)),
+                Ok(None)
+            );
+
+            set_caller(accounts.alice);
+            bridge.claim(transfer_id, 60).unwrap();
+            vote(&mut bridge, accounts.alice, transfer_id, succeeded()).unwrap();
+            assert_eq!(
+                bridge.get_settlement(TransferKey::Nonce(transfer_id)),
+                Ok(None)
+            );
+            vote(&mut bridge, accounts.bob, transfer_id, succeeded()).unwrap();
+
+            let settlement = Settlement {
+                tx_hash: [0x30; 32],
+                log_index: 0,
+            };
+            assert_eq!(
+                bridge.get_settlement(TransferKey::Nonce(transfer_id)),
+                Ok(Some(settlement))
+            );
+            assert_eq!(
+                bridge.get_settlement(TransferKey::Global(global_id)),
+                Ok(Some(settlement))
+            );
+            assert_eq!(
+                bridge.get_settlement(TransferKey::Nonce(transfer_id + 1)),
+                Ok(None)
+            );
+            let recorded = ink_env::test::recorded_events().any(|event| {
+                matches!(
+                    <Event as scale::Decode>::decode(&mut &event.data[..]),
+                    Ok(Event::SuccessfulTransfer(SuccessfulTransfer { settlement: recorded, .. }))
+                        if recorded == settlement
+                )
+            });
+            assert!(recorded);
+        }
+
+        #[ink::test]
+        fn batch_processing_is_atomic_or_best_effort() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let first = queue_transfer(&mut bridge, None);
+            let second = queue_transfer(&mut bridge, None);
+            let outcome = failed(FailureCode::Other);
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                bridge.process_transfers(
+                    ink_prelude::vec![(first, outcome.clone()), (second + 1, outcome.clone())],
+                    true
+                ),
+                Err(Error::NotFound(second + 1))
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.process_transfers(
+                    ink_prelude::vec![
+                        (first, outcome.clone()),
+                        (second + 1, outcome.clone()),
+                        (second, outcome.clone()),
+                    ],
+                    false
+                ),
+                Ok(ink_prelude::vec![
+                    Ok(()),
+                    Err(Error::NotFound(second + 1)),
+                    Ok(())
+                ])
+            );
+            assert_eq!(transfer(&bridge, second).status, TransferStatus::Queued);
+            assert_eq!(bridge.vote_count(second, &outcome), 1);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.process_transfers(ink_prelude::vec![(second, outcome)], false),
+                Err(Error::ExecutorPermissionDenied)
+            );
+        }
+
+        #[ink::test]
+        fn status_lists_stay_dense() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let ids: Vec<u128> = (0..4).map(|_| queue_transfer(&mut bridge, None)).collect();
+            let listed = |transfers: Vec<Transfer>| -> Vec<u128> {
+                transfers.iter().map(|transfer| transfer.id).collect()
+            };
+            assert_eq!(listed(bridge.list_queued(0, 10).unwrap()), ids);
+
+            // Removing a transfer moves the last one into its position.
+            fail(&mut bridge, ids[1], FailureCode::Other);
+            assert_eq!(
+                listed(bridge.list_queued(0, 10).unwrap()),
+                ink_prelude::vec![ids[0], ids[3], ids[2]]
+            );
+            assert_eq!(
+                listed(bridge.list_failed(0, 10).unwrap()),
+                ink_prelude::vec![ids[1]]
+            );
+            set_caller(accounts.alice);
+            bridge.claim(ids[0], 60).unwrap();
+            assert_eq!(
+                listed(bridge.list_queued(0, 10).unwrap()),
+                ink_prelude::vec![ids[2], ids[3]]
+            );
+            assert_eq!(
+                listed(bridge.list_in_flight(0, 10).unwrap()),
+                ink_prelude::vec![ids[0]]
+            );
+            assert_eq!(bridge.get_queue_depth(), Ok(2));
+            assert_eq!(bridge.get_in_flight_count(), Ok(1));
+            assert_eq!(bridge.get_failed_count(), Ok(1));
+
+            assert_eq!(
+                listed(bridge.list_queued(1, 1).unwrap()),
+                ink_prelude::vec![ids[3]]
+            );
+            assert_eq!(bridge.list_queued(2, 10), Ok(Vec::new()));
+        }
+
+        #[ink::test]
+        fn transfers_are_indexed_by_sender() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let ids: Vec<u128> = (0..3).map(|_| queue_transfer(&mut bridge, None)).collect();
+            fail(&mut bridge, ids[0], FailureCode::Other);
+
+            assert_eq!(bridge.get_sender_transfer_count(accounts.django), Ok(3));
+            let page = bridge.list_by_sender(accounts.django, 0, 2).unwrap();
+            assert_eq!(
+                page.iter()
+                    .map(|transfer| (transfer.id, transfer.status))
+                    .collect::<Vec<_>>(),
+                ink_prelude::vec![
+                    (ids[0], TransferStatus::Failed),
+                    (ids[1], TransferStatus::Queued)
+                ]
+            );
+            let page = bridge.list_by_sender(accounts.django, 2, 2).unwrap();
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].id, ids[2]);
+            assert_eq!(bridge.list_by_sender(accounts.django, 3, 2), Ok(Vec::new()));
+
+            assert_eq!(bridge.get_sender_transfer_count(accounts.eve), Ok(0));
+            assert_eq!(bridge.list_by_sender(accounts.eve, 0, 10), Ok(Vec::new()));
+            assert_eq!(
+                bridge
+                    .list_by_destination(DESTINATION, 0, 10)
+                    .unwrap()
+                    .len(),
+                3
+            );
+        }
+
+        #[ink::test]
+        fn transfers_are_found_by_global_id() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let global_id = transfer(&bridge, transfer_id).global_id;
+
+            assert_eq!(
+                bridge.get_transfer(TransferKey::Global(global_id)),
+                Ok(Some(transfer(&bridge, transfer_id)))
+            );
+            assert_eq!(
+                bridge.get_transfer(TransferKey::Global(Hash::from([0xff; 32]))),
+                Ok(None)
+            );
+            assert_eq!(
+                bridge.get_transfer(TransferKey::Nonce(transfer_id + 1)),
+                Ok(None)
+            );
+            assert_eq!(bridge.get_chain_id(), Ok(42));
+
+            // The id covers the chain, so another deployment never computes the same one.
+            let id = bridge.global_id(1, accounts.django, AccountId::from(TOKEN), DESTINATION, 100);
+            assert_ne!(
+                id,
+                bridge.global_id(2, accounts.django, AccountId::from(TOKEN), DESTINATION, 100)
+            );
+            bridge.chain_id = 43;
+            assert_ne!(
+                id,
+                bridge.global_id(1, accounts.django, AccountId::from(TOKEN), DESTINATION, 100)
+            );
+        }
+
+        #[ink::test]
+        fn liabilities_follow_outstanding_transfers() {
+            let accounts = accounts();
+            let token = AccountId::from(TOKEN);
+            let mut bridge = new_bridge();
+            bridge
+                .register_token(token, [0x11; 20], 18, TokenMode::LockRelease)
+                .unwrap();
+            let delivered = queue_transfer(&mut bridge, None);
+            let failed_id = queue_transfer(&mut bridge, None);
+            assert_eq!(bridge.liabilities(token), 200);
+
+            // Failed transfers are still owed until they are refunded.
+            fail(&mut bridge, failed_id, FailureCode::Other);
+            assert_eq!(bridge.queued_amounts.get(token), Some(100));
+            assert_eq!(bridge.refundable_amounts.get(token), Some(100));
+            assert_eq!(bridge.liabilities(token), 200);
+
+            set_caller(accounts.alice);
+            bridge.claim(delivered, 60).unwrap();
+            assert_eq!(bridge.liabilities(token), 200);
+            vote(&mut bridge, accounts.alice, delivered, succeeded()).unwrap();
+            vote(&mut bridge, accounts.bob, delivered, succeeded()).unwrap();
+            assert_eq!(bridge.liabilities(token), 100);
+
+            bridge.collected_fees.insert(token, &5);
+            assert_eq!(bridge.liabilities(token), 105);
+            // In mint/burn mode the transfer amounts are burned, so only the fees are owed.
+            bridge.tokens.insert(
+                token,
+                &TokenInfo {
+                    eth_address: [0x11; 20],
+                    decimals: 18,
+                    mode: TokenMode::MintBurn,
+                    enabled: true,
+                },
+            );
+            assert_eq!(bridge.liabilities(token), 5);
+        }
+
+        #[ink::test]
+        fn transfer_expires_after_its_deadline() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            assert_eq!(
+                bridge.transfer(AccountId::from(TOKEN), 100, DESTINATION, None, Some(now())),
+                Err(Error::InvalidDeadline(now()))
+            );
+            let deadline = now() + 12;
+            let transfer_id = queue_transfer(&mut bridge, Some(deadline));
+            let open_ended = queue_transfer(&mut bridge, None);
+
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.expire(transfer_id),
+                Err(Error::DeadlineNotReached {
+                    id: transfer_id,
+                    deadline: Some(deadline),
+                })
+            );
+            advance_blocks(2);
+            assert_eq!(
+                bridge.expire(transfer_id),
+                Err(Error::DeadlineNotReached {
+                    id: transfer_id,
+                    deadline: Some(deadline),
+                })
+            );
+            advance_blocks(1);
+            bridge.expire(transfer_id).unwrap();
+            let expired = transfer(&bridge, transfer_id);
+            assert_eq!(expired.status, TransferStatus::Failed);
+            assert_eq!(expired.failure_code(), Some(FailureCode::Expired));
+            assert_eq!(
+                bridge.expire(open_ended),
+                Err(Error::DeadlineNotReached {
+                    id: open_ended,
+                    deadline: None,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn cancel_is_limited_to_sender_within_window() {
+            let accounts = accounts();
+            let mut bridge = new_bridge();
+            set_caller(accounts.bob);
+            assert_eq!(
+                bridge.set_cancel_window(12),
+                Err(Error::AdminPermissionDenied)
+            );
+            set_caller(accounts.alice);
+            bridge.set_cancel_window(12).unwrap();
+            assert_eq!(bridge.get_cancel_window(), Ok(12));
+            let transfer_id = queue_transfer(&mut bridge, None);
+            let closed_at = now() + 12;
+
+            set_caller(accounts.eve);
+            assert_eq!(
+                bridge.cancel(transfer_id),
+                Err(Error::CancelPermissionDenied)
+            );
+            advance_blocks(3);
+            set_caller(accounts.django);
+            assert_eq!(
+                bridge.cancel(transfer_id),
+                Err(Error::CancelWindowClosed { closed_at })
+            );
+            assert_eq!(
+                transfer(&bridge, transfer_id).status,
+                TransferStatus::Queued
+            );
         }
     }
 }
@@ -25,6 +25,8 @@ pub mod erc20 {
         /// Mapping of the token amount which an account is allowed to withdraw
         /// from another account.
         allowances: Mapping<(AccountId, AccountId), Balance>,
+        /// Account which is allowed to mint and burn tokens.
+        minter: AccountId,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -48,6 +50,15 @@ pub mod erc20 {
         value: Balance,
     }
 
+    /// Event emitted when the minter role is handed over.
+    #[ink(event)]
+    pub struct MinterChanged {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        minter: AccountId,
+    }
+
     /// The ERC-20 error types.
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -56,6 +67,10 @@ pub mod erc20 {
         InsufficientBalance,
         /// Returned if not enough allowance to fulfill a request is available.
         InsufficientAllowance,
+        /// Returned if the caller is not the minter.
+        NotMinter,
+        /// Returned if minting would overflow the total supply.
+        Overflow,
     }
 
     /// The ERC-20 result type.
@@ -77,6 +92,7 @@ pub mod erc20 {
             let caller = Self::env().caller();
             self.balances.insert(&caller, &initial_supply);
             self.total_supply = initial_supply;
+            self.minter = caller;
             Self::env().emit_event(Transfer {
                 from: None,
                 to: Some(caller),
@@ -164,6 +180,87 @@ pub mod erc20 {
             Ok(())
         }
 
+        /// Returns the account which is allowed to mint and burn tokens.
+        #[ink(message)]
+        pub fn minter(&self) -> AccountId {
+            self.minter
+        }
+
+        /// Hands the minter role over to `minter`.
+        ///
+        /// A `MinterChanged` event is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller is not the current minter.
+        #[ink(message)]
+        pub fn set_minter(&mut self, minter: AccountId) -> Result<()> {
+            let previous = self.env().caller();
+            if previous != self.minter {
+                return Err(Error::NotMinter)
+            }
+            self.minter = minter;
+            self.env().emit_event(MinterChanged { previous, minter });
+            Ok(())
+        }
+
+        /// Creates `value` new tokens on the account `to`.
+        ///
+        /// On success a `Transfer` event with `from: None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller is not the minter.
+        ///
+        /// Returns `Overflow` error if the total supply would exceed `Balance::MAX`.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            if self.env().caller() != self.minter {
+                return Err(Error::NotMinter)
+            }
+            // Every balance is part of the total supply, so it can't overflow on its own.
+            let total_supply = self.total_supply.checked_add(value).ok_or(Error::Overflow)?;
+            let to_balance = self.balance_of_impl(&to);
+            self.balances.insert(to, &(to_balance + value));
+            self.total_supply = total_supply;
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+
+        /// Destroys `value` tokens of the caller's account.
+        ///
+        /// On success a `Transfer` event with `to: None` is emitted.
+        ///
+        /// # Errors
+        ///
+        /// Returns `NotMinter` error if the caller is not the minter.
+        ///
+        /// Returns `InsufficientBalance` error if there are not enough tokens on
+        /// the caller's account balance.
+        #[ink(message)]
+        pub fn burn(&mut self, value: Balance) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != self.minter {
+                return Err(Error::NotMinter)
+            }
+            let caller_balance = self.balance_of_impl(&caller);
+            if caller_balance < value {
+                return Err(Error::InsufficientBalance)
+            }
+            self.balances.insert(caller, &(caller_balance - value));
+            self.total_supply -= value;
+            self.env().emit_event(Transfer {
+                from: Some(caller),
+                to: None,
+                value,
+            });
+            Ok(())
+        }
+
         /// Transfers `value` tokens on the behalf of `from` to the account `to`.
         ///
         /// This can be used to allow a contract to transfer tokens on ones behalf and/or
@@ -493,6 +590,77 @@ pub mod erc20 {
             )
         }
 
+        #[ink::test]
+        fn mint_works() {
+            let mut erc20 = Erc20::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            // Alice is the minter and mints 10 tokens to Bob.
+            assert_eq!(erc20.mint(accounts.bob, 10), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 10);
+            assert_eq!(erc20.total_supply(), 110);
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(
+                &emitted_events[1],
+                None,
+                Some(AccountId::from([0x02; 32])),
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let mut erc20 = Erc20::new(100);
+
+            // Alice is the minter and burns 10 of her tokens.
+            assert_eq!(erc20.burn(10), Ok(()));
+            assert_eq!(erc20.balance_of(AccountId::from([0x01; 32])), 90);
+            assert_eq!(erc20.total_supply(), 90);
+            assert_eq!(erc20.burn(91), Err(Error::InsufficientBalance));
+
+            let emitted_events = ink_env::test::recorded_events().collect::<Vec<_>>();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(AccountId::from([0x01; 32])),
+                None,
+                10,
+            );
+        }
+
+        #[ink::test]
+        fn mint_and_burn_require_minter() {
+            let mut erc20 = Erc20::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            // Alice hands the minter role over to Bob.
+            assert_eq!(erc20.set_minter(accounts.bob), Ok(()));
+            assert_eq!(erc20.minter(), accounts.bob);
+
+            // Alice is not able to mint or burn anymore.
+            assert_eq!(erc20.mint(accounts.alice, 10), Err(Error::NotMinter));
+            assert_eq!(erc20.burn(10), Err(Error::NotMinter));
+            assert_eq!(erc20.set_minter(accounts.alice), Err(Error::NotMinter));
+            assert_eq!(erc20.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn mint_rejects_supply_overflow() {
+            let mut erc20 = Erc20::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>();
+
+            // Minting beyond `Balance::MAX` fails without touching the supply.
+            assert_eq!(erc20.mint(accounts.bob, Balance::MAX - 100), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::Overflow));
+            assert_eq!(erc20.balance_of(accounts.bob), Balance::MAX - 100);
+            assert_eq!(erc20.total_supply(), Balance::MAX);
+        }
+
         /// For calculating the event topic hash.
         struct PrefixedValue<'a, 'b, T> {
             pub prefix: &'a [u8],
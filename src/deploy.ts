@@ -42,7 +42,7 @@ export async function deploySubstrateErc20(
 
   const erc = await deploySubstrateContract(
     substrateTokenOwner,
-    initSupply,
+    [initSupply],
     api,
     wasm,
     metadata
@@ -55,7 +55,12 @@ export async function deploySubstrateErc20(
 export async function deploySubstrateBridge(
   contractOwner: KeyringPair,
   api: ApiPromise,
-  address: AccountId
+  token: AccountId,
+  ethToken: string,
+  decimals: number,
+  chainId: number,
+  ethBridge: string,
+  ethChainId: number
 ): Promise<ContractPromise> {
   console.log(`current folder is ${process.cwd()}`);
   const wasm = fs.readFileSync("contracts/bridge/target/ink/bridge.wasm");
@@ -63,19 +68,58 @@ export async function deploySubstrateBridge(
 
   const bridge = await deploySubstrateContract(
     contractOwner,
-    address,
+    [chainId],
     api,
     wasm,
     metadata
   );
   console.log("Substrate Bridge deployed to:", bridge.address.toString());
 
+  const registerTokenTx = bridge.tx.registerToken(
+    {},
+    token,
+    Buffer.from(ethToken.substring(2), "hex"),
+    decimals,
+    "LockRelease"
+  );
+  await new Promise<void>(async (resolve) => {
+    const unsub = await registerTokenTx.signAndSend(
+      contractOwner,
+      ({ status }) => {
+        if (status.isInBlock || status.isFinalized) {
+          unsub();
+          resolve();
+        }
+      }
+    );
+  });
+
+  const setEthSourceTx = bridge.tx.setEthSource(
+    {},
+    {
+      chainId: ethChainId,
+      bridge: Buffer.from(ethBridge.substring(2), "hex"),
+    },
+    true
+  );
+  await new Promise<void>(async (resolve) => {
+    const unsub = await setEthSourceTx.signAndSend(
+      contractOwner,
+      ({ status }) => {
+        if (status.isInBlock || status.isFinalized) {
+          unsub();
+          resolve();
+        }
+      }
+    );
+  });
+
   return bridge;
 }
 
 async function deploySubstrateContract(
   contractOwner: KeyringPair,
-  initArgs: any,
+  initArgs: any[],
   api: ApiPromise,
   wasm: Buffer,
   metadata: Buffer
@@ -96,14 +140,14 @@ async function deploySubstrateCode(
   api: ApiPromise,
   code: CodePromise,
   contractOwner: KeyringPair,
-  initArgs: any
+  initArgs: any[]
 ): Promise<{ address: string; hash: Hash }> {
   // maximum gas to be consumed for the instantiation. if limit is too small the instantiation will fail.
   const gasLimit: BigNumberish = 100000 * 1000000;
   // a limit to how much Balance to be used to pay for the storage created by the instantiation
   // if null is passed, unlimited balance can be used
   const storageDepositLimit = null;
-  const tx = code.tx.new({ gasLimit, storageDepositLimit }, initArgs);
+  const tx = code.tx.new({ gasLimit, storageDepositLimit }, ...initArgs);
 
   const myPromise: Promise<{ address: string; hash: Hash }> = new Promise(
     async (resolve) => {
@@ -7,6 +7,11 @@ import { AccountId } from "@polkadot/types/interfaces/runtime/types";
 import { Bytes } from "@polkadot/types-codec/extended/Bytes";
 import { IEventRecord } from "@polkadot/types/types/events";
 
+// Time a claimed Substrate transfer stays reserved for this executor.
+const CLAIM_LEASE_DURATION_MS = 10 * 60 * 1000;
+// Transfers whose deadline is closer than this aren't delivered anymore.
+const DEADLINE_SAFETY_MARGIN_MS = 2 * 60 * 1000;
+
 export class Executor {
   ethBridgeContract: Bridge;
   ethTokenContract: MyToken;
@@ -76,11 +81,29 @@ export class Executor {
 
     const decoded = this.substrateBridgeContract.abi.decodeEvent(contract_evt);
     // @ts-ignore
-    let [id, from, _, amount, timestamp]: [
+    let [
+      id,
+      globalId,
+      from,
+      token,
+      _,
+      grossAmount,
+      fee,
+      amount,
+      tip,
+      deadline,
+      timestamp,
+    ]: [
       number,
       string,
       string,
+      string,
+      string,
+      number,
       number,
+      number,
+      number,
+      number | null,
       number
     ] = decoded.args.map((arg) => arg.toJSON());
     if (decoded.event.identifier != "Queued" || id <= lastID) {
@@ -90,40 +113,106 @@ export class Executor {
       localLastID = id;
     }
 
-    const toRaw: Buffer = Buffer.from(decoded.args[2].toU8a());
+    const toRaw: Buffer = Buffer.from(decoded.args[4].toU8a());
     const to = toRaw.toString("hex");
 
     console.log("received substrate queued event: ", {
       id,
+      globalId,
       from,
+      token,
       to,
+      grossAmount,
+      fee,
       amount,
+      tip,
+      deadline: deadline ? new Date(deadline) : null,
       timestamp: new Date(timestamp),
     });
-    this.ethTokenContract.transfer(to as string, amount).then(async (tx) => {
-      await tx.wait();
-
-      const processTx = await this.substrateBridgeContract.tx.processTransfer(
-        {},
-        this.substrateApi.createType("u128", id),
-        true
+    if (deadline && deadline - Date.now() < DEADLINE_SAFETY_MARGIN_MS) {
+      console.log(
+        `skipping substrate transfer ${id}, its deadline is too close`
       );
-      const txPromise: Promise<void> = new Promise(async (resolve) => {
-        const unsub = await processTx.signAndSend(
-          this.substrateBridgeExecutor,
-          ({ status }) => {
-            if (status.isInBlock || status.isFinalized) {
-              unsub();
-              resolve();
-            }
+      return [true, localLastID];
+    }
+    this.claimSubstrateTransfer(id)
+      .then(() => this.ethTokenContract.transfer(to as string, amount))
+      .then(async (tx) => {
+        const receipt = await tx.wait();
+
+        await this.callSubstrateBridge(
+          "processTransfer",
+          "Voted",
+          this.substrateApi.createType("u128", id),
+          {
+            Succeeded: {
+              txHash: receipt.transactionHash,
+              logIndex: receipt.logs[0].logIndex,
+            },
           }
         );
-      });
-      await txPromise;
-    });
+      })
+      .catch((e) =>
+        console.log(`substrate transfer ${id} was not delivered: `, e)
+      );
     return [true, localLastID];
   }
 
+  // Claiming closes the sender's cancel window and keeps other executors from
+  // delivering the transfer while the tokens are sent on Ethereum.
+  private async claimSubstrateTransfer(id: number): Promise<void> {
+    await this.callSubstrateBridge(
+      "claim",
+      "Claimed",
+      this.substrateApi.createType("u128", id),
+      CLAIM_LEASE_DURATION_MS
+    );
+  }
+
+  // Dry-runs the bridge message first, since a message returning an error is
+  // still included in a block, and resolves once the call emitted
+  // `expectedEvent`.
+  private async callSubstrateBridge(
+    message: string,
+    expectedEvent: string,
+    ...params: unknown[]
+  ): Promise<void> {
+    const { result, output } = await this.substrateBridgeContract.query[
+      message
+    ](this.substrateBridgeExecutor.address, {}, ...params);
+    // @ts-ignore
+    if (result.isErr || output?.isErr) {
+      throw new Error(
+        `${message} failed: ${JSON.stringify(
+          result.isErr ? result.toHuman() : output?.toHuman()
+        )}`
+      );
+    }
+    const tx = this.substrateBridgeContract.tx[message]({}, ...params);
+    await new Promise<void>(async (resolve, reject) => {
+      const unsub = await tx.signAndSend(
+        this.substrateBridgeExecutor,
+        ({ status, dispatchError, contractEvents }) => {
+          if (!status.isInBlock && !status.isFinalized) {
+            return;
+          }
+          unsub();
+          if (dispatchError) {
+            reject(new Error(`${message} failed: ${dispatchError.toString()}`));
+          } else if (
+            !contractEvents?.some(
+              ({ event }) => event.identifier === expectedEvent
+            )
+          ) {
+            reject(new Error(`${message} was reverted`));
+          } else {
+            resolve();
+          }
+        }
+      );
+    });
+  }
+
   private async processEthEvent(
     id: BigNumber,
     from: string | null,
@@ -139,13 +228,20 @@ export class Executor {
     const value = this.substrateApi.createType("Balance", amount);
     const accountId = this.substrateApi.createType("AccountId", to);
 
-    const TransferTx = this.substrateTokenContract.tx.transfer(
+    const source = {
+      chainId: (await this.ethBridgeContract.provider.getNetwork()).chainId,
+      bridge: Buffer.from(this.ethBridgeContract.address.substring(2), "hex"),
+    };
+    const releaseTx = this.substrateBridgeContract.tx.release(
       {},
+      source,
+      this.substrateApi.createType("u128", id),
+      this.substrateTokenContract.address,
       accountId,
       value
     );
 
-    const unsub = await TransferTx.signAndSend(
+    const unsub = await releaseTx.signAndSend(
       this.substrateBridgeExecutor,
       async (result) => {
         if (result.isError) {
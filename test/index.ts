@@ -23,6 +23,7 @@ import { ContractCallOutcome } from "@polkadot/api-contract/types";
 
 const initSupply = 100000;
 const transferAmount: number = 100;
+const substrateChainId: number = 42;
 
 let ethUser: SignerWithAddress;
 let bridgeEthClient: Bridge;
@@ -105,7 +106,12 @@ describe("Test", function () {
     substrateBridgeContract = await deploySubstrateBridge(
       substrateBridgeOwner,
       api,
-      substrateTokenContract.address
+      substrateTokenContract.address,
+      ethTokenContractAddress,
+      2,
+      substrateChainId,
+      bridgeContractAddress,
+      (await ethers.provider.getNetwork()).chainId
     );
     ethBridgeClient = Bridge__factory.connect(
       bridgeContractAddress,
@@ -144,7 +150,7 @@ describe("Test", function () {
 
     const toSenderTransferTx2 = substrateTokenContract.tx.transfer(
       {},
-      substrateBridgeOwner.address,
+      substrateBridgeContract.address,
       transferAmount
     );
     const txPromise2: Promise<void> = new Promise(async (resolve) => {
@@ -163,7 +169,7 @@ describe("Test", function () {
       await substrateTokenContract.query.balanceOf(
         substrateBridgeOwner.address,
         { gasLimit: -1 },
-        substrateBridgeOwner.address
+        substrateBridgeContract.address
       );
     expect(txResult2.output?.toHuman()).to.equal(transferAmount.toString());
 
@@ -246,8 +252,11 @@ describe("Test", function () {
     const b = Buffer.from(ethReceiver.address.substring(2), "hex");
     const bridgeTx = substrateBridgeContract.tx.transfer(
       {},
+      substrateTokenContract.address,
       BN.from(transferAmount),
-      b
+      b,
+      null,
+      null
     );
 
     const bridgeTxPromise: Promise<void> = new Promise(async (resolve) => {